@@ -1,5 +1,5 @@
 use std::ops::{Add, Sub, Mul, Div, Neg};
-use ::num_traits::{Trig, Pow, ArithmeticOps, SignedArithmeticOps};
+use ::num_traits::{Trig, Pow, Exp, ArithmeticOps, SignedArithmeticOps, FractionOps, NumCast, One, CheckedOps, SaturatingOps};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Complex<T> {
@@ -9,7 +9,48 @@ pub struct Complex<T> {
 
 impl<T> Complex<T> {
     pub fn new(real: T, imag: T) -> Complex<T> {
-        Complex{real: real, imag: imag}
+        Complex{real, imag}
+    }
+
+    /// Applies the same function to both the real and imaginary
+    /// parts.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    ///
+    /// let rounded = Complex::new(1.4 as f64, 2.6).map(|x| x.round());
+    /// assert_eq!(rounded, Complex::new(1.0, 3.0));
+    /// assert_eq!(Complex::new(3, -4).map(|x| x as f64 / 2.0), Complex::new(1.5, -2.0));
+    /// ```
+    pub fn map<U, F>(self, f: F) -> Complex<U> where F: Fn(T) -> U {
+        Complex::new(f(self.real), f(self.imag))
+    }
+
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    ///
+    /// let (real, imag) = Complex::new(1.5, -2.0).into_tuple();
+    /// assert_eq!(Complex::new(real, imag), Complex::new(1.5, -2.0));
+    /// ```
+    pub fn into_tuple(self) -> (T, T) {
+        (self.real, self.imag)
+    }
+
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    ///
+    /// let array = Complex::new(1.5, -2.0).into_array();
+    /// assert_eq!(array, [1.5, -2.0]);
+    /// assert_eq!(Complex::from_array(array), Complex::new(1.5, -2.0));
+    /// ```
+    pub fn into_array(self) -> [T; 2] {
+        [self.real, self.imag]
+    }
+
+    /// Creates a complex number from `[real, imag]`.
+    pub fn from_array(parts: [T; 2]) -> Complex<T> {
+        let [real, imag] = parts;
+        Complex::new(real, imag)
     }
 }
 impl<T> Complex<T> where T: SignedArithmeticOps {
@@ -18,6 +59,43 @@ impl<T> Complex<T> where T: SignedArithmeticOps {
     }
 }
 
+impl<T> Complex<T> where T: PartialOrd + Default {
+    /// Classifies which quadrant of the complex plane the number lies
+    /// in, without needing any trigonometry.
+    ///
+    /// Each quadrant includes the axis at the start of it, going
+    /// anticlockwise. So the positive real axis is in quadrant 1, the
+    /// positive imaginary axis is in quadrant 2, the negative real
+    /// axis is in quadrant 3, and the negative imaginary axis is in
+    /// quadrant 4. The origin isn't in any quadrant, so it gives 0.
+    ///
+    /// The element type's default value is used as zero, which is the
+    /// case for all of the built in number types.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// assert_eq!(Complex::new(3, 0).quadrant(), 1);
+    /// assert_eq!(Complex::new(0, 3).quadrant(), 2);
+    /// assert_eq!(Complex::new(-3, 0).quadrant(), 3);
+    /// assert_eq!(Complex::new(0, -3).quadrant(), 4);
+    /// assert_eq!(Complex::new(0, 0).quadrant(), 0);
+    /// ```
+    pub fn quadrant(&self) -> u8 {
+        let zero = T::default();
+        if self.real > zero && self.imag >= zero {
+            1
+        } else if self.real <= zero && self.imag > zero {
+            2
+        } else if self.real < zero && self.imag <= zero {
+            3
+        } else if self.real >= zero && self.imag < zero {
+            4
+        } else {
+            0
+        }
+    }
+}
+
 impl<T> Complex<T> where T: Pow + ArithmeticOps + Copy  {
     pub fn magnitude(self) -> T {
         (self.real.pow(2) + self.imag.pow(2)).sqrt()
@@ -68,6 +146,194 @@ impl<T> Complex<T> where T: Trig + Pow + ArithmeticOps + Copy  {
     pub fn to_polar(self) -> (T, T) {
         (self.magnitude(), self.angle())
     }
+
+    /// Multiplies the magnitude by `mag_factor` and rotates the angle
+    /// by `angle_offset` in one step.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// use std::f32;
+    ///
+    /// let a = Complex::new(1.0 as f32, 1.0);
+    /// let b = a.scale_polar(2.0, f32::consts::FRAC_PI_2);
+    /// assert!((b.magnitude() - 2.0*a.magnitude()).abs() < f32::EPSILON*4.0);
+    /// assert!((b.angle() - (a.angle() + f32::consts::FRAC_PI_2)).abs() < f32::EPSILON);
+    /// assert!((b.real + 2.0).abs() < f32::EPSILON*4.0);
+    /// assert!((b.imag - 2.0).abs() < f32::EPSILON*4.0);
+    /// ```
+    pub fn scale_polar(self, mag_factor: T, angle_offset: T) -> Complex<T> {
+        let (magnitude, angle) = self.to_polar();
+        Complex::from_polar(magnitude*mag_factor, angle + angle_offset)
+    }
+}
+
+impl<T> Complex<T> where T: Trig + Pow + Exp + ArithmeticOps + Copy {
+    /// The complex exponential, `e^(a+jb) = e^a·(cos(b) + j·sin(b))`.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// use std::f64;
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// let minus_one = Complex::new(0.0, f64::consts::PI).exp();
+    /// assert!(minus_one.real.approx_eq(-1.0));
+    /// assert!(minus_one.imag.approx_eq(0.0));
+    ///
+    /// let e = Complex::new(1.0, 0.0).exp();
+    /// assert!(e.real.approx_eq(f64::consts::E));
+    /// ```
+    pub fn exp(self) -> Complex<T> {
+        Complex::from_polar(self.real.exp(), self.imag)
+    }
+}
+
+impl<T> Complex<T> where T: Trig + Pow + Exp + ArithmeticOps + FractionOps + NumCast + Copy {
+    /// Raises the complex number to a real power, using the principal
+    /// value of the angle (between -π and π).
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// let i = Complex::new(-1.0 as f64, 0.0).powf(0.5);
+    /// assert!(i.real.approx_eq(0.0));
+    /// assert!(i.imag.approx_eq(1.0));
+    /// ```
+    pub fn powf(self, p: T) -> Complex<T> {
+        let (magnitude, angle) = self.to_polar();
+        Complex::from_polar(magnitude.powf(p), angle*p)
+    }
+
+    /// The principal square root, with a non-negative real part.
+    pub fn sqrt(self) -> Complex<T> {
+        self.powf(T::from_f64(0.5))
+    }
+
+    /// The principal cube root. This is the same as `powf(1/3)` and
+    /// the first of `nth_roots(3)`, so the cube root of a negative
+    /// real number is not the negative real root.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// let root = Complex::new(-8.0 as f64, 0.0).cbrt();
+    /// assert!(root.real.approx_eq(1.0));
+    /// assert!(root.imag.approx_eq(3.0_f64.sqrt()));
+    /// ```
+    pub fn cbrt(self) -> Complex<T> {
+        self.powf(T::from_f64(3.0).recip())
+    }
+
+    /// All `n` of the `n`th roots, in order of increasing angle
+    /// starting from the principal root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn nth_roots(self, n: usize) -> Vec<Complex<T>> {
+        assert!(n > 0, "There is no 0th root");
+        let (magnitude, angle) = self.to_polar();
+        let order = T::from_usize(n);
+        let root_magnitude = magnitude.powf(order.recip());
+        (0..n)
+            .map(|k| Complex::from_polar(root_magnitude, (angle + T::two_pi()*T::from_usize(k)) / order))
+            .collect()
+    }
+}
+
+impl<T> Complex<T> where T: SignedArithmeticOps + One + Copy {
+    /// Raises the complex number to an integer power.
+    ///
+    /// This uses exponentiation by squaring with the complex `Mul`,
+    /// so integer element types stay exact (no floating point is
+    /// involved). Negative powers are computed as the reciprocal of
+    /// the positive power, which truncates for integer element types
+    /// the same way integer division does.
+    ///
+    /// # Panics
+    ///
+    /// Zero has no reciprocal, so a negative power of zero divides by
+    /// zero. For integer element types that panics, just as integer
+    /// division does. Float element types give NaN instead.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// assert_eq!(Complex::new(1, 1).powi(2), Complex::new(0, 2));
+    /// assert_eq!(Complex::new(3, 4).powi(0), Complex::new(1, 0));
+    /// assert_eq!(Complex::new(0.0, 2.0).powi(-1), Complex::new(0.0, -0.5));
+    /// ```
+    pub fn powi(self, n: i32) -> Complex<T> {
+        let one = Complex::new(T::one(), T::one() - T::one());
+        let mut result = one;
+        let mut base = self;
+        // unsigned, since -i32::MIN doesn't fit in an i32
+        let mut remaining = n.unsigned_abs();
+        while remaining > 0 {
+            if remaining % 2 == 1 {
+                result = result * base;
+            }
+            remaining /= 2;
+            if remaining > 0 {
+                base = base * base;
+            }
+        }
+
+        if n < 0 {
+            one / result
+        } else {
+            result
+        }
+    }
+}
+
+impl<T> Complex<T> where T: CheckedOps + Copy {
+    /// Adds two complex numbers, or gives `None` if either part
+    /// overflows.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// assert_eq!(Complex::new(1, 2).checked_add(Complex::new(3, 4)), Some(Complex::new(4, 6)));
+    /// assert_eq!(Complex::new(i32::MAX, 0).checked_add(Complex::new(1, 0)), None);
+    /// ```
+    pub fn checked_add(self, other: Complex<T>) -> Option<Complex<T>> {
+        Some(Complex::new(self.real.checked_add(other.real)?, self.imag.checked_add(other.imag)?))
+    }
+
+    /// Multiplies two complex numbers, or gives `None` if any of the
+    /// intermediate products or sums overflow.
+    pub fn checked_mul(self, other: Complex<T>) -> Option<Complex<T>> {
+        let real = self.real.checked_mul(other.real)?.checked_sub(self.imag.checked_mul(other.imag)?)?;
+        let imag = self.real.checked_mul(other.imag)?.checked_add(self.imag.checked_mul(other.real)?)?;
+        Some(Complex::new(real, imag))
+    }
+}
+
+impl<T> Complex<T> where T: SaturatingOps + Copy {
+    /// Adds two complex numbers, clamping each part to the bounds of
+    /// the element type.
+    pub fn saturating_add(self, other: Complex<T>) -> Complex<T> {
+        Complex::new(self.real.saturating_add(other.real), self.imag.saturating_add(other.imag))
+    }
+
+    /// Multiplies two complex numbers, clamping to the bounds of the
+    /// element type.
+    ///
+    /// Each part is worked out exactly before it is clamped, so
+    /// products that are out of range on their own but cancel out
+    /// still give the right answer.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    ///
+    /// let big = Complex::new(40000, 40000);
+    /// assert_eq!(big.saturating_mul(big), Complex::new(0, i32::MAX));
+    /// ```
+    pub fn saturating_mul(self, other: Complex<T>) -> Complex<T> {
+        let real = self.real.saturating_mul_sub(other.real, self.imag, other.imag);
+        let imag = self.real.saturating_mul_add(other.imag, self.imag, other.real);
+        Complex::new(real, imag)
+    }
 }
 
 impl<T> Add for Complex<T> where T: ArithmeticOps + Copy {
@@ -102,6 +368,34 @@ impl<T> Sub for Complex<T> where T: ArithmeticOps + Copy {
     }
 }
 
+impl<T> Add<T> for Complex<T> where T: ArithmeticOps + Copy {
+    type Output = Complex<T>;
+
+    /// Adding a real number only changes the real part.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// assert_eq!(Complex::new(1.0, 2.0) + 3.0, Complex::new(4.0, 2.0));
+    /// ```
+    fn add(self, other: T) -> Self {
+        Complex::new(self.real + other, self.imag)
+    }
+}
+
+impl<T> Sub<T> for Complex<T> where T: ArithmeticOps + Copy {
+    type Output = Complex<T>;
+
+    /// Subtracting a real number only changes the real part.
+    ///
+    /// ```
+    /// use worthe_signals::complex::Complex;
+    /// assert_eq!(Complex::new(1.0, 2.0) - 3.0, Complex::new(-2.0, 2.0));
+    /// ```
+    fn sub(self, other: T) -> Self {
+        Complex::new(self.real - other, self.imag)
+    }
+}
+
 impl<T> Mul for Complex<T> where T: ArithmeticOps + Copy {
     type Output = Complex<T>;
 
@@ -156,7 +450,6 @@ impl<T> Neg for Complex<T> where T: SignedArithmeticOps + Copy {
 #[cfg(test)]
 mod tests {  
     use super::*;
-    use std::i32;
     
     quickcheck! {
         fn add_zero(real: i32, imag: i32) -> bool {
@@ -199,6 +492,107 @@ mod tests {
             let com2 = Complex::new(real2, imag2);
             com1 + com2 == com2 + com1
         }
+
+        fn add_real_scalar(real: i32, imag: i32, scalar: i32) -> bool {
+            let com = Complex::new(real, imag);
+            com + scalar == com + Complex::new(scalar, 0)
+        }
+        fn sub_real_scalar(real: i32, imag: i32, scalar: i32) -> bool {
+            let com = Complex::new(real, imag);
+            com - scalar == com - Complex::new(scalar, 0)
+        }
+
+        fn powi_is_repeated_multiplication(real: i8, imag: i8, n: u8) -> bool {
+            let com = Complex::new(real as i64, imag as i64);
+            let n = n % 6;
+            let expected = (0..n).fold(Complex::new(1, 0), |acc, _| acc * com);
+            com.powi(n as i32) == expected
+        }
+    }
+
+    #[test]
+    fn quadrants_of_diagonals() {
+        assert_eq!(Complex::new(1, 1).quadrant(), 1);
+        assert_eq!(Complex::new(-1, 1).quadrant(), 2);
+        assert_eq!(Complex::new(-1, -1).quadrant(), 3);
+        assert_eq!(Complex::new(1, -1).quadrant(), 4);
+    }
+
+    #[test]
+    fn powi_integer_is_exact() {
+        // (3+4i)^25 has components larger than 2^53, so any route
+        // through f64 would lose the low digits.
+        let result = Complex::new(3i64, 4i64).powi(25);
+        assert_eq!(result, Complex::new(-110422359737857437, -276811749100242716));
+    }
+
+    #[test]
+    fn powi_of_most_negative_exponent() {
+        // i to any multiple of 4 is 1
+        assert_eq!(Complex::new(0.0_f64, 1.0).powi(i32::MIN), Complex::new(1.0, 0.0));
+        assert_eq!(Complex::new(0.0_f64, -1.0).powi(i32::MIN + 2), Complex::new(-1.0, 0.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn powi_of_integer_zero_with_negative_exponent_panics() {
+        Complex::new(0, 0).powi(-1);
+    }
+
+    #[test]
+    fn large_integer_products_saturate() {
+        let a = Complex::new(100_000i32, 100_000);
+        let b = a;
+        // exactly 2e10j, which would wrap around to a negative number
+        assert_eq!(a.saturating_mul(b), Complex::new(0, i32::MAX));
+        assert_eq!(a.checked_mul(b), None);
+        assert_eq!(Complex::new(3i32, 4).checked_mul(Complex::new(1, -2)), Some(Complex::new(11, -2)));
+
+        // MAX·2 - MAX·1 only overflows partway through
+        let cancelling = Complex::new(i32::MAX, i32::MAX).saturating_mul(Complex::new(2, 1));
+        assert_eq!(cancelling, Complex::new(i32::MAX, i32::MAX));
+        assert_eq!(Complex::new(i32::MIN, i32::MIN).saturating_mul(Complex::new(1, 1)), Complex::new(0, i32::MIN));
+        assert_eq!(Complex::new(i64::MIN, i64::MIN).saturating_mul(Complex::new(i64::MIN, i64::MIN)), Complex::new(0, i64::MAX));
+        assert_eq!(Complex::new(2u8, 1).saturating_mul(Complex::new(200, 100)), Complex::new(u8::MAX, u8::MAX));
+        assert_eq!(Complex::new(1u64, u64::MAX).saturating_mul(Complex::new(1, u64::MAX)), Complex::new(0, u64::MAX));
+
+        let max = Complex::new(i32::MAX, i32::MIN);
+        assert_eq!(max.checked_add(Complex::new(1, 0)), None);
+        assert_eq!(max.checked_add(Complex::new(0, -1)), None);
+        assert_eq!(max.saturating_add(Complex::new(5, -5)), max);
+        assert_eq!(max.checked_add(Complex::new(-1, 1)), Some(Complex::new(i32::MAX - 1, i32::MIN + 1)));
+    }
+
+    fn assert_close(a: Complex<f64>, b: Complex<f64>) {
+        assert!((a - b).magnitude() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn cbrt_agrees_with_powf_and_roots() {
+        let inputs = [
+            Complex::new(8.0, 0.0),
+            Complex::new(-8.0, 0.0),
+            Complex::new(0.0, -27.0),
+            Complex::new(-3.0, 4.0),
+            Complex::new(-2.0, -0.5),
+        ];
+        for &z in inputs.iter() {
+            let root = z.cbrt();
+            assert_close(root.powi(3), z);
+            assert_close(root, z.powf(1.0 / 3.0));
+            assert_close(root, z.nth_roots(3)[0]);
+        }
+    }
+
+    #[test]
+    fn nth_roots_are_all_roots() {
+        let z = Complex::new(1.5, -2.0);
+        let roots = z.nth_roots(5);
+        assert_eq!(roots.len(), 5);
+        for root in roots {
+            assert_close(root.powi(5), z);
+        }
+        assert_close(Complex::new(-4.0, 0.0).sqrt(), Complex::new(0.0, 2.0));
     }
 }
 
@@ -0,0 +1,834 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+use ::sinusoid::Sinusoid;
+use super::{analytic_signal, autocorrelate, goertzel};
+use super::db::amplitude_to_db;
+
+/// Removes the 2π jumps from a sequence of phase angles, so that
+/// the phase changes smoothly.
+///
+/// Whenever the phase changes by more than π between consecutive
+/// samples, it is assumed to have wrapped around and a multiple of
+/// 2π is added to bring it back in line.
+///
+/// ```
+/// use worthe_signals::dsp::unwrap_phase;
+/// use std::f64::consts::PI;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let wrapped = vec![0.0, 0.75*PI, -0.5*PI, 0.25*PI];
+/// let unwrapped = unwrap_phase(&wrapped);
+/// assert!(unwrapped[2].approx_eq(1.5*PI));
+/// assert!(unwrapped[3].approx_eq(2.25*PI));
+/// ```
+pub fn unwrap_phase<T>(phase: &[T]) -> Vec<T> where T: Float {
+    let mut result = Vec::with_capacity(phase.len());
+    let mut offset = T::zero();
+    for (i, &angle) in phase.iter().enumerate() {
+        if i > 0 {
+            let mut delta = angle - phase[i-1];
+            while delta > T::pi() {
+                delta = delta - T::two_pi();
+                offset = offset - T::two_pi();
+            }
+            while delta < -T::pi() {
+                delta = delta + T::two_pi();
+                offset = offset + T::two_pi();
+            }
+        }
+        result.push(angle + offset);
+    }
+    result
+}
+
+/// The fraction of adjacent pairs of samples that change sign.
+///
+/// Exact zeros are counted as positive, so going from 0 to a negative
+/// sample is a crossing, but going from 0 to a positive sample isn't.
+/// With fewer than two samples there are no pairs, and the rate is 0.
+///
+/// ```
+/// use worthe_signals::dsp::zero_crossing_rate;
+///
+/// assert_eq!(zero_crossing_rate(&[1.0 as f32, -1.0, 1.0, -1.0]), 1.0);
+/// assert_eq!(zero_crossing_rate(&[0.5 as f32, 0.5, 0.5, 0.5]), 0.0);
+/// assert_eq!(zero_crossing_rate(&[1.0 as f32, 0.0, 2.0, -1.0, -1.0]), 0.25);
+/// ```
+pub fn zero_crossing_rate<T>(samples: &[T]) -> T where T: Float {
+    if samples.len() < 2 {
+        return T::zero();
+    }
+    let crossings = samples.windows(2)
+        .filter(|pair| (pair[0] < T::zero()) != (pair[1] < T::zero()))
+        .count();
+    T::from_usize(crossings) / T::from_usize(samples.len() - 1)
+}
+
+/// Sums the samples with Kahan's compensated summation.
+///
+/// Adding a small number to a large running total loses the small
+/// number's low bits. This keeps track of what was lost and feeds it
+/// back into the next addition, so the error stays around one
+/// rounding step no matter how many samples there are, rather than
+/// growing with the length. That matters most for long f32 buffers.
+///
+/// ```
+/// use worthe_signals::dsp::kahan_sum;
+///
+/// let samples = vec![0.1 as f32; 1_000_000];
+/// assert!((kahan_sum(&samples) - 100_000.0).abs() < 0.01);
+/// ```
+pub fn kahan_sum<T>(samples: &[T]) -> T where T: Float {
+    compensated_sum(samples.iter().cloned())
+}
+
+fn compensated_sum<T, I>(values: I) -> T where T: Float, I: Iterator<Item = T> {
+    let mut sum = T::zero();
+    let mut compensation = T::zero();
+    for x in values {
+        let y = x - compensation;
+        let t = sum + y;
+        compensation = (t - sum) - y;
+        sum = t;
+    }
+    sum
+}
+
+/// The root mean square level of the samples.
+///
+/// An empty buffer has no level, and gives 0.
+///
+/// ```
+/// use worthe_signals::dsp::rms;
+///
+/// assert_eq!(rms(&[3.0 as f32, -3.0, 3.0, -3.0]), 3.0);
+/// assert_eq!(rms(&[1.0 as f32, 7.0]), 5.0);
+/// assert_eq!(rms::<f32>(&[]), 0.0);
+/// ```
+pub fn rms<T>(samples: &[T]) -> T where T: Float {
+    if samples.is_empty() {
+        return T::zero();
+    }
+    let energy = compensated_sum(samples.iter().map(|&x| x*x));
+    (energy / T::from_usize(samples.len())).sqrt()
+}
+
+/// The largest absolute value of the samples.
+///
+/// An empty buffer has no level, and gives 0.
+///
+/// ```
+/// use worthe_signals::dsp::peak;
+///
+/// assert_eq!(peak(&[0.5 as f32, -2.0, 1.5]), 2.0);
+/// assert_eq!(peak::<f32>(&[]), 0.0);
+/// ```
+pub fn peak<T>(samples: &[T]) -> T where T: Float {
+    samples.iter().fold(T::zero(), |max, &x| if x.abs() > max { x.abs() } else { max })
+}
+
+/// The energy weighted mean time of a signal in seconds, which
+/// describes whether a sound's energy comes early, like a drum hit,
+/// or late, like a swell.
+///
+/// Each sample is weighted by its square, and the first sample is at
+/// 0 seconds. A signal with no energy has no centroid, and gives 0.
+///
+/// ```
+/// use worthe_signals::dsp::temporal_centroid;
+///
+/// let samples = [0.0 as f32, 1.0, 0.0, -1.0, 0.0];
+/// assert_eq!(temporal_centroid(&samples, 2.0), 1.0);
+/// ```
+pub fn temporal_centroid<T>(samples: &[T], sample_rate: T) -> T where T: Float {
+    let energy = compensated_sum(samples.iter().map(|&x| x*x));
+    if energy == T::zero() {
+        return T::zero();
+    }
+    let weighted = compensated_sum(samples.iter().enumerate().map(|(n, &x)| x*x*T::from_usize(n)));
+    weighted / energy / sample_rate
+}
+
+/// The fraction of samples at or beyond `±full_scale`, from 0 to 1.
+///
+/// An empty buffer has nothing clipped, and gives 0.
+///
+/// ```
+/// use worthe_signals::dsp::clipping_ratio;
+///
+/// assert_eq!(clipping_ratio(&[0.5 as f32, -1.0, 1.2, 0.0], 1.0), 0.5);
+/// ```
+pub fn clipping_ratio<T>(samples: &[T], full_scale: T) -> T where T: Float {
+    if samples.is_empty() {
+        return T::zero();
+    }
+    let clipped = samples.iter().filter(|x| x.abs() >= full_scale).count();
+    T::from_usize(clipped) / T::from_usize(samples.len())
+}
+
+/// Whether more than `threshold` of the samples are clipped, as
+/// measured by `clipping_ratio`.
+///
+/// A threshold of 0 flags a buffer with any clipped samples at all.
+///
+/// ```
+/// use worthe_signals::dsp::is_clipped;
+///
+/// let samples = [0.5 as f32, -1.0, 0.2, 0.0];
+/// assert!(is_clipped(&samples, 1.0, 0.0));
+/// assert!(!is_clipped(&samples, 1.0, 0.25));
+/// ```
+pub fn is_clipped<T>(samples: &[T], full_scale: T, threshold: T) -> bool where T: Float {
+    clipping_ratio(samples, full_scale) > threshold
+}
+
+/// Measures a filter's impulse response, by feeding it a unit impulse
+/// followed by zeros, one sample at a time.
+///
+/// The filter can be anything that processes one sample at a time,
+/// like the `process` method of the filters in `iir`.
+///
+/// ```
+/// use worthe_signals::dsp::impulse_response;
+/// use worthe_signals::dsp::iir::DcBlocker;
+///
+/// let mut blocker = DcBlocker::new(0.5 as f32);
+/// let response = impulse_response(&mut |x| blocker.process(x), 4);
+/// assert_eq!(response, vec![1.0, -0.5, -0.25, -0.125]);
+/// ```
+pub fn impulse_response<T, F>(filter: &mut F, len: usize) -> Vec<T> where T: Float, F: FnMut(T) -> T {
+    (0..len)
+        .map(|n| filter(if n == 0 { T::one() } else { T::zero() }))
+        .collect()
+}
+
+/// Measures a filter's step response, by feeding it a constant input
+/// of one, one sample at a time.
+///
+/// ```
+/// use worthe_signals::dsp::step_response;
+/// use worthe_signals::dsp::iir::DcBlocker;
+///
+/// let mut blocker = DcBlocker::new(0.5 as f32);
+/// let response = step_response(&mut |x| blocker.process(x), 4);
+/// assert_eq!(response, vec![1.0, 0.5, 0.25, 0.125]);
+/// ```
+pub fn step_response<T, F>(filter: &mut F, len: usize) -> Vec<T> where T: Float, F: FnMut(T) -> T {
+    (0..len).map(|_| filter(T::one())).collect()
+}
+
+/// Detects the fundamental frequency of a monophonic signal from its
+/// autocorrelation.
+///
+/// A periodic signal correlates strongly with itself delayed by one
+/// period, so this searches the autocorrelation for its strongest peak
+/// between the lags matching `max_freq` and `min_freq`. The peak is
+/// refined between lags by fitting a parabola through it and its
+/// neighbours.
+///
+/// If there is no peak in the range that is at least half of the
+/// signal's energy, the signal isn't clearly periodic, and `None` is
+/// returned. `None` is also returned if `min_freq` isn't positive or
+/// `max_freq` isn't above it, since there's no range of lags to search.
+///
+/// ```
+/// use worthe_signals::dsp::pitch_autocorr;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// let tone = Sinusoid::new(1.0 as f64, 250.0, 0.0);
+/// let samples = tone.sample_range(0.0, 0.1, 8000.0);
+/// let pitch = pitch_autocorr(&samples, 8000.0, 50.0, 1000.0).unwrap();
+/// assert!((pitch - 250.0).abs() < 1.0);
+///
+/// assert_eq!(pitch_autocorr(&[0.0 as f64; 800], 8000.0, 50.0, 1000.0), None);
+/// ```
+pub fn pitch_autocorr<T>(samples: &[T], sample_rate: T, min_freq: T, max_freq: T) -> Option<T> where T: Float {
+    let has_lag_range = min_freq > T::zero() && max_freq > min_freq;
+    if !has_lag_range {
+        return None;
+    }
+
+    let r = autocorrelate(samples);
+    if r.is_empty() || r[0] <= T::zero() {
+        return None;
+    }
+
+    let min_lag = (sample_rate / max_freq).to_usize().max(1);
+    let max_lag = (sample_rate / min_freq).to_usize().saturating_add(1);
+    let max_lag = max_lag.min(r.len() - 1);
+
+    let two = T::from_f64(2.0);
+    let mut best: Option<usize> = None;
+    for lag in min_lag..max_lag {
+        let is_peak = r[lag] > r[lag-1] && r[lag] >= r[lag+1];
+        let is_clear = two*r[lag] >= r[0];
+        let is_highest = match best {
+            Some(b) => r[lag] > r[b],
+            None => true
+        };
+        if is_peak && is_clear && is_highest {
+            best = Some(lag);
+        }
+    }
+
+    best.map(|lag| {
+        let (offset, _) = parabolic_interp(r[lag-1], r[lag], r[lag+1]);
+        sample_rate / (T::from_usize(lag) + offset)
+    })
+}
+
+/// Fits a parabola through three evenly spaced samples around a local
+/// maximum, and returns the offset of the parabola's peak from the
+/// middle sample (in samples, between -0.5 and 0.5 for a true local
+/// maximum) along with the parabola's value at that peak.
+///
+/// This is used to find a peak in a spectrum or a correlation more
+/// precisely than the spacing between samples. If the three samples
+/// are in a straight line there is no parabola, so the middle sample
+/// is returned as it is.
+///
+/// ```
+/// use worthe_signals::dsp::parabolic_interp;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// assert_eq!(parabolic_interp(0.5 as f64, 1.0, 0.5), (0.0, 1.0));
+///
+/// // samples of 1 - (x - 0.25)² at x = -1, 0, 1
+/// let (offset, value) = parabolic_interp(-0.5625 as f64, 0.9375, 0.4375);
+/// assert!(offset.approx_eq(0.25));
+/// assert!(value.approx_eq(1.0));
+/// ```
+pub fn parabolic_interp<T>(y_minus: T, y0: T, y_plus: T) -> (T, T) where T: Float {
+    let two = T::from_f64(2.0);
+    let curvature = y_minus - two*y0 + y_plus;
+    if curvature == T::zero() {
+        return (T::zero(), y0);
+    }
+    let offset = (y_minus - y_plus) / (two*curvature);
+    (offset, y0 - (y_minus - y_plus)*offset / T::from_f64(4.0))
+}
+
+/// Measures how far a filter has shifted the phase of a tone, by
+/// comparing the component at `freq` in its input and output.
+///
+/// The input and output should be the same length and line up in
+/// time. The result is the output's phase minus the input's, wrapped
+/// to between -π and π, so a delay gives a negative shift. A delay of
+/// `d` samples shifts the phase by `-2π·freq·d/sample_rate`, so the
+/// delay can be recovered from the shift as long as it is less than
+/// half a period.
+///
+/// ```
+/// use worthe_signals::dsp::measure_phase_shift;
+/// use worthe_signals::sinusoid::Sinusoid;
+/// use std::f64::consts::FRAC_PI_2;
+///
+/// let input = Sinusoid::new(1.0 as f64, 100.0, 0.0).sample_range(0.0, 0.1, 8000.0);
+/// let output = Sinusoid::new(1.0 as f64, 100.0, -FRAC_PI_2).sample_range(0.0, 0.1, 8000.0);
+/// let shift = measure_phase_shift(&input, &output, 100.0, 8000.0);
+/// assert!((shift + FRAC_PI_2).abs() < 1e-9);
+/// ```
+pub fn measure_phase_shift<T>(input: &[T], output: &[T], freq: T, sample_rate: T) -> T where T: Float {
+    let before = goertzel(input, freq, sample_rate);
+    let after = goertzel(output, freq, sample_rate);
+    (after * before.conjugate()).angle()
+}
+
+/// The instantaneous frequency of a signal, in Hz, from how fast the
+/// phase of its analytic signal turns.
+///
+/// The phase is unwrapped and differenced between consecutive
+/// samples, so entry `n` of the result is the frequency between
+/// samples `n` and `n+1`, and there is one fewer entry than there are
+/// samples. The analytic signal treats the samples as though they
+/// repeat, so the first and last few entries are thrown off by the
+/// jump between the end and the start.
+///
+/// ```
+/// use worthe_signals::dsp::instantaneous_frequency;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// let tone = Sinusoid::new(1.0 as f64, 128.0, 0.0).sample_range(0.0, 0.25, 1024.0);
+/// let frequency = instantaneous_frequency(&tone, 1024.0);
+/// assert_eq!(frequency.len(), 255);
+/// assert!(frequency.iter().all(|f| (f - 128.0).abs() < 1e-6));
+/// ```
+pub fn instantaneous_frequency<T>(samples: &[T], sample_rate: T) -> Vec<T> where T: Float {
+    let phase: Vec<T> = analytic_signal(samples).into_iter().map(|x| x.angle()).collect();
+    let phase = unwrap_phase(&phase);
+    let scale = sample_rate / T::two_pi();
+    phase.windows(2).map(|pair| (pair[1] - pair[0])*scale).collect()
+}
+
+/// Logarithmically spaced frequencies from `f_start` to `f_stop`
+/// inclusive, so each frequency is the same ratio above the last.
+///
+/// A single point gives just `f_start`. Both frequencies need to be
+/// positive.
+///
+/// ```
+/// use worthe_signals::dsp::log_frequency_sweep;
+///
+/// let decades = log_frequency_sweep(10.0 as f64, 10000.0, 4);
+/// for (f, expected) in decades.iter().zip(&[10.0, 100.0, 1000.0, 10000.0]) {
+///     assert!((f - expected).abs() < 1e-9 * expected);
+/// }
+/// ```
+pub fn log_frequency_sweep<T>(f_start: T, f_stop: T, points: usize) -> Vec<T> where T: Float {
+    if points == 1 {
+        return vec![f_start];
+    }
+    let ratio = f_stop / f_start;
+    (0..points)
+        .map(|k| f_start * ratio.powf(T::from_usize(k) / T::from_usize(points - 1)))
+        .collect()
+}
+
+/// Evaluates a digital filter's transfer function at each frequency,
+/// giving `(frequency, magnitude in dB, phase in degrees)` for a Bode
+/// plot.
+///
+/// `filter` is the transfer function H(z), and is evaluated on the
+/// unit circle at `z = e^(j2πf/sample_rate)`. The phase is wrapped to
+/// between -180 and 180 degrees.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::bode;
+///
+/// // a one sample delay, H(z) = z⁻¹
+/// let points = bode(|z: Complex<f64>| z.powi(-1), &[0.0, 12000.0], 48000.0);
+/// assert!(points[0].1.abs() < 1e-9);
+/// assert!((points[1].2 + 90.0).abs() < 1e-9);
+/// ```
+pub fn bode<T, F>(filter: F, freqs: &[T], sample_rate: T) -> Vec<(T, T, T)> where T: Float, F: Fn(Complex<T>) -> Complex<T> {
+    let degrees = T::from_f64(180.0) / T::pi();
+    freqs.iter().map(|&f| {
+        let z = Complex::from_polar(T::one(), T::two_pi()*f/sample_rate);
+        let response = filter(z);
+        (f, amplitude_to_db(response.magnitude()), response.angle()*degrees)
+    }).collect()
+}
+
+/// Finds the amplitude and phase of the sinusoid at a known frequency
+/// that best fits the samples, taken starting at t=0, in the least
+/// squares sense.
+///
+/// The samples are projected onto cosine and sine at `freq`, and the
+/// 2×2 least squares problem for the in-phase and quadrature
+/// amplitudes is solved exactly, so unlike a single DFT bin this
+/// doesn't need a whole number of periods. There is no DC term in the
+/// fit, so any offset should be removed first.
+///
+/// At 0 Hz, or at the Nyquist frequency where the sine is always 0,
+/// only the cosine can be fitted.
+///
+/// ```
+/// use worthe_signals::dsp::fit_sinusoid;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// let tone = Sinusoid::new(0.8 as f64, 13.3, -1.2);
+/// let fit = fit_sinusoid(&tone.sample_range(0.0, 0.37, 200.0), 13.3, 200.0);
+/// assert!((fit.amplitude - 0.8).abs() < 1e-9);
+/// assert!((fit.phase + 1.2).abs() < 1e-9);
+/// ```
+pub fn fit_sinusoid<T>(samples: &[T], freq: T, sample_rate: T) -> Sinusoid<T> where T: Float {
+    let omega = T::two_pi() * freq / sample_rate;
+    let mut sums = [T::zero(); 5];
+    for (n, &x) in samples.iter().enumerate() {
+        let (c, s) = ((omega*T::from_usize(n)).cos(), (omega*T::from_usize(n)).sin());
+        sums[0] = sums[0] + c*c;
+        sums[1] = sums[1] + c*s;
+        sums[2] = sums[2] + s*s;
+        sums[3] = sums[3] + x*c;
+        sums[4] = sums[4] + x*s;
+    }
+    let [cc, cs, ss, xc, xs] = sums;
+
+    let determinant = cc*ss - cs*cs;
+    let (a, b) = if determinant.abs() > T::epsilon()*cc*ss {
+        ((xc*ss - xs*cs) / determinant, (xs*cc - xc*cs) / determinant)
+    } else if cc > T::zero() {
+        (xc / cc, T::zero())
+    } else {
+        (T::zero(), T::zero())
+    };
+    // a·cos(ωn) + b·sin(ωn) = A·cos(ωn + θ) where A·cos(θ) = a and A·sin(θ) = -b
+    Sinusoid::new((a*a + b*b).sqrt(), freq, (-b).atan2(a))
+}
+
+/// An exponentially weighted moving average,
+/// `y[n] = α·x[n] + (1-α)·y[n-1]`.
+///
+/// Each update moves the average a fraction `alpha` of the way
+/// towards the new sample, so older samples are weighted less and
+/// less as time goes on. After a step in the input, the average gets
+/// `1 - 1/e` of the way to the new level in about `1/alpha` samples.
+/// The average starts at 0.
+///
+/// ```
+/// use worthe_signals::dsp::Ewma;
+///
+/// let mut level = Ewma::new(0.5 as f32);
+/// assert_eq!(level.update(1.0), 0.5);
+/// assert_eq!(level.update(1.0), 0.75);
+/// assert_eq!(level.value(), 0.75);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ewma<T> {
+    pub alpha: T,
+    value: T
+}
+
+impl<T> Ewma<T> where T: Float {
+    pub fn new(alpha: T) -> Ewma<T> {
+        Ewma {
+            alpha,
+            value: T::zero()
+        }
+    }
+
+    /// Adds a sample to the average, and returns the new average.
+    pub fn update(&mut self, x: T) -> T {
+        self.value = self.alpha*x + (T::one() - self.alpha)*self.value;
+        self.value
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    pub fn reset(&mut self) {
+        self.value = T::zero();
+    }
+}
+
+/// Keeps track of the mean, variance and range of a stream of
+/// samples, without storing them.
+///
+/// The mean and variance are updated with Welford's algorithm, which
+/// avoids the loss of precision from subtracting two large sums. The
+/// variance is the population variance, dividing by the number of
+/// samples. With no samples yet, the mean and variance are 0 and
+/// there's no minimum or maximum.
+///
+/// ```
+/// use worthe_signals::dsp::RunningStats;
+///
+/// let mut stats = RunningStats::new();
+/// for &x in &[2.0 as f64, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+///     stats.push(x);
+/// }
+/// assert_eq!(stats.count(), 8);
+/// assert_eq!(stats.mean(), 5.0);
+/// assert_eq!(stats.variance(), 4.0);
+/// assert_eq!(stats.std_dev(), 2.0);
+/// assert_eq!(stats.min(), Some(2.0));
+/// assert_eq!(stats.max(), Some(9.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningStats<T> {
+    count: usize,
+    mean: T,
+    sum_of_squares: T,
+    min: Option<T>,
+    max: Option<T>
+}
+
+impl<T> RunningStats<T> where T: Float {
+    pub fn new() -> RunningStats<T> {
+        RunningStats {
+            count: 0,
+            mean: T::zero(),
+            sum_of_squares: T::zero(),
+            min: None,
+            max: None
+        }
+    }
+
+    pub fn push(&mut self, x: T) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean = self.mean + delta / T::from_usize(self.count);
+        self.sum_of_squares = self.sum_of_squares + delta*(x - self.mean);
+        self.min = Some(self.min.map_or(x, |min| if x < min { x } else { min }));
+        self.max = Some(self.max.map_or(x, |max| if x > max { x } else { max }));
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> T {
+        self.mean
+    }
+
+    pub fn variance(&self) -> T {
+        if self.count == 0 {
+            T::zero()
+        } else {
+            self.sum_of_squares / T::from_usize(self.count)
+        }
+    }
+
+    pub fn std_dev(&self) -> T {
+        self.variance().sqrt()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.max
+    }
+}
+
+impl<T> Default for RunningStats<T> where T: Float {
+    fn default() -> RunningStats<T> {
+        RunningStats::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+
+    /// A streaming FIR filter, as a closure.
+    fn fir(taps: Vec<f64>) -> impl FnMut(f64) -> f64 {
+        let mut history = vec![0.0; taps.len()];
+        move |x| {
+            history.rotate_right(1);
+            history[0] = x;
+            history.iter().zip(taps.iter()).map(|(h, t)| h*t).sum()
+        }
+    }
+
+    #[test]
+    fn pitch_of_sawtooth() {
+        use ::signal::Signal;
+        use ::waves::SawtoothWave;
+
+        let saw: SawtoothWave<f64> = SawtoothWave::new(1.0, 220.0, 0.0);
+        let samples = saw.sample_range(0.0, 0.25, 8000.0);
+        let pitch = pitch_autocorr(&samples, 8000.0, 80.0, 1000.0).unwrap();
+        assert!((pitch - 220.0).abs() < 3.0, "Pitch was {}", pitch);
+    }
+
+    #[test]
+    fn noise_has_no_pitch() {
+        use ::dsp::noise;
+
+        let samples: Vec<f64> = noise::white(2000, 1.0, 3);
+        assert_eq!(pitch_autocorr(&samples, 8000.0, 80.0, 1000.0), None);
+    }
+
+    #[test]
+    fn pitch_needs_a_positive_frequency_range() {
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 250.0, 0.0);
+        let samples = tone.sample_range(0.0, 0.1, 8000.0);
+        assert_eq!(pitch_autocorr(&samples, 8000.0, 0.0, 1000.0), None);
+        assert_eq!(pitch_autocorr(&samples, 8000.0, -50.0, 1000.0), None);
+        assert_eq!(pitch_autocorr(&samples, 8000.0, 500.0, 100.0), None);
+        assert_eq!(pitch_autocorr(&samples, 8000.0, f64::NAN, 1000.0), None);
+    }
+
+    #[test]
+    fn kahan_sum_is_closer_than_naive_sum() {
+        let samples: Vec<f32> = (0..1_000_000).map(|n| 0.1 + (n % 7) as f32 * 0.01).collect();
+        let reference: f64 = samples.iter().map(|&x| x as f64).sum();
+        let naive: f32 = samples.iter().fold(0.0, |acc, &x| acc + x);
+        let kahan = kahan_sum(&samples);
+        let naive_error = (naive as f64 - reference).abs();
+        let kahan_error = (kahan as f64 - reference).abs();
+        assert!(naive_error > 1.0, "Naive error was only {}", naive_error);
+        assert!(kahan_error < 0.01, "Kahan error was {}", kahan_error);
+    }
+
+    #[test]
+    fn rms_of_long_f32_buffer() {
+        let samples = vec![0.3_f32; 1 << 22];
+        assert!((rms(&samples) - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn temporal_centroid_follows_the_energy() {
+        use ::dsp::exp_decay;
+        let decay: Vec<f64> = exp_decay(1.0, 0.05, 1000, 1000.0);
+        let swell: Vec<f64> = decay.iter().rev().cloned().collect();
+        let early = temporal_centroid(&decay, 1000.0);
+        let late = temporal_centroid(&swell, 1000.0);
+        // the energy decays with a time constant of 25 ms
+        assert!((early - 0.025).abs() < 1e-3, "Decay centroid was {}", early);
+        assert!((late - (0.999 - early)).abs() < 1e-9, "Swell centroid was {}", late);
+        assert_eq!(temporal_centroid(&[0.0_f64; 16], 1000.0), 0.0);
+    }
+
+    #[test]
+    fn clipping_ratio_counts_saturated_samples() {
+        use ::sinusoid::Sinusoid;
+        // a tone at twice full scale, hard clipped
+        let tone: Sinusoid<f64> = Sinusoid::new(2.0, 1.0, 0.0);
+        let samples: Vec<f64> = tone.sample_count(0.0, 1000, 1000.0).into_iter()
+            .map(|x| x.clamp(-1.0, 1.0))
+            .collect();
+        let saturated = samples.iter().filter(|x| x.abs() == 1.0).count();
+        assert!(saturated > 0);
+        assert_eq!(clipping_ratio(&samples, 1.0), saturated as f64 / 1000.0);
+
+        let quieter: Vec<f64> = samples.iter().map(|x| 0.5*x).collect();
+        assert_eq!(clipping_ratio(&quieter, 1.0), 0.0);
+        assert!(!is_clipped(&quieter, 1.0, 0.0));
+    }
+
+    #[test]
+    fn fir_impulse_response_is_its_taps() {
+        let taps = vec![0.5, -0.25, 1.0, 0.125];
+        let response = impulse_response(&mut fir(taps.clone()), 6);
+        assert_eq!(response, vec![0.5, -0.25, 1.0, 0.125, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn step_response_is_running_sum_of_impulse_response() {
+        let taps = vec![0.5, -0.25, 1.0, 0.125];
+        let impulse = impulse_response(&mut fir(taps.clone()), 6);
+        let step = step_response(&mut fir(taps), 6);
+        let mut sum = 0.0;
+        for (i, s) in impulse.iter().zip(step.iter()) {
+            sum += i;
+            assert!(s.approx_eq(sum));
+        }
+    }
+
+    #[test]
+    fn ewma_step_response_time_constant() {
+        let alpha = 0.01_f64;
+        let mut average = Ewma::new(alpha);
+        let time_constant = (-1.0 / (1.0 - alpha).ln()).round() as usize;
+        let mut y = 0.0;
+        for _ in 0..time_constant {
+            y = average.update(2.0);
+        }
+        let expected = 2.0 * (1.0 - (-1.0_f64).exp());
+        assert!((y - expected).abs() < 1e-2, "Value after one time constant was {}", y);
+
+        for _ in 0..2000 {
+            y = average.update(2.0);
+        }
+        assert!((y - 2.0).abs() < 1e-6);
+        average.reset();
+        assert_eq!(average.value(), 0.0);
+    }
+
+    #[test]
+    fn phase_shift_of_fir_matches_group_delay() {
+        use ::dsp::{convolve, group_delay};
+        use ::sinusoid::Sinusoid;
+
+        let taps = [0.05_f64, 0.1, 0.15, 0.2, 0.2, 0.15, 0.1, 0.05];
+        // 250 Hz is the 5th of 65 points from 0 to the 4 kHz Nyquist frequency
+        let expected_delay = group_delay(&taps, 65)[4];
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 250.0, 0.3);
+        let input = tone.sample_range(0.0, 0.2, 8000.0);
+        let output = convolve(&input, &taps);
+
+        // skip the first period, while the filter is still filling up,
+        // which leaves a whole number of periods to measure
+        let settled = 32;
+        let shift = measure_phase_shift(&input[settled..], &output[settled..input.len()], 250.0, 8000.0);
+        let delay = -shift * 8000.0 / (2.0 * std::f64::consts::PI * 250.0);
+        assert!((delay - expected_delay).abs() < 1e-6, "Delay was {}, expected {}", delay, expected_delay);
+        assert!((delay - 3.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn instantaneous_frequency_of_chirp_rises_linearly() {
+        use ::dsp::chirp;
+
+        let samples: Vec<f64> = chirp(200.0, 1000.0, 1.0, 8192.0);
+        assert_eq!(samples.len(), 8192);
+        let frequency = instantaneous_frequency(&samples, 8192.0);
+        for (n, &f) in frequency.iter().enumerate().take(7900).skip(300) {
+            let expected = 200.0 + 800.0*(n as f64 + 0.5)/8192.0;
+            assert!((f - expected).abs() < 10.0, "Frequency at {} was {}, expected {}", n, f, expected);
+        }
+    }
+
+    #[test]
+    fn log_sweep_has_constant_ratio() {
+        let sweep: Vec<f64> = log_frequency_sweep(20.0, 20000.0, 31);
+        assert_eq!(sweep.len(), 31);
+        assert!((sweep[0] - 20.0).abs() < 1e-9);
+        assert!((sweep[30] - 20000.0).abs() < 1e-6);
+        let ratio = sweep[1] / sweep[0];
+        for pair in sweep.windows(2) {
+            assert!(pair[1] > pair[0]);
+            assert!((pair[1] / pair[0] - ratio).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn bode_of_low_pass_falls() {
+        // a one pole low pass, H(z) = (1 - a) / (1 - a·z⁻¹)
+        let a = 0.9;
+        let low_pass = |z: Complex<f64>| Complex::new(1.0 - a, 0.0) / (Complex::new(1.0, 0.0) - z.powi(-1)*Complex::new(a, 0.0));
+        let points = bode(low_pass, &log_frequency_sweep(10.0, 20000.0, 20), 48000.0);
+        assert!(points[0].1.abs() < 0.1, "DC gain was {} dB", points[0].1);
+        for pair in points.windows(2) {
+            assert!(pair[1].1 < pair[0].1, "Magnitudes should fall, but were {:?}", points);
+            assert!(pair[1].2 < 0.0);
+        }
+    }
+
+    #[test]
+    fn fit_sinusoid_through_noise() {
+        use ::dsp::noise;
+
+        let tone: Sinusoid<f64> = Sinusoid::new(1.3, 47.0, 2.1);
+        let clean = tone.sample_range(0.0, 0.5, 1000.0);
+        let noise: Vec<f64> = noise::white(clean.len(), 0.5, 17);
+        let noisy: Vec<f64> = clean.iter().zip(noise.iter()).map(|(x, n)| x + n).collect();
+
+        let fit = fit_sinusoid(&noisy, 47.0, 1000.0);
+        assert_eq!(fit.frequency, 47.0);
+        assert!((fit.amplitude - 1.3).abs() < 0.05, "Amplitude was {}", fit.amplitude);
+        assert!((fit.phase - 2.1).abs() < 0.05, "Phase was {}", fit.phase);
+    }
+
+    #[test]
+    fn fit_sinusoid_at_dc_and_nyquist() {
+        let dc = fit_sinusoid(&[0.5_f64; 10], 0.0, 100.0);
+        assert!(dc.amplitude.approx_eq(0.5));
+        assert!(dc.phase.approx_eq(0.0));
+
+        let alternating: Vec<f64> = (0..10).map(|n| if n % 2 == 0 { -2.0 } else { 2.0 }).collect();
+        let nyquist = fit_sinusoid(&alternating, 50.0, 100.0);
+        assert!((nyquist.amplitude - 2.0).abs() < 1e-9);
+        assert!((nyquist.phase.abs() - std::f64::consts::PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn running_stats_match_batch_computation() {
+        use ::dsp::noise;
+
+        // a large offset is where the naive sum of squares loses precision
+        let samples: Vec<f64> = noise::white(5000, 2.0, 4).iter().map(|x| x + 1e6).collect();
+        let mut stats = RunningStats::new();
+        for &x in &samples {
+            stats.push(x);
+        }
+
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert_eq!(stats.count(), 5000);
+        assert!((stats.mean() - mean).abs() < 1e-6);
+        assert!((stats.variance() - variance).abs() < 1e-6, "Variance was {}, expected {}", stats.variance(), variance);
+        assert!(stats.min().unwrap() >= 1e6 - 2.0);
+        assert!(stats.max().unwrap() < 1e6 + 2.0);
+
+        let empty: RunningStats<f64> = RunningStats::default();
+        assert_eq!(empty.variance(), 0.0);
+        assert_eq!(empty.max(), None);
+    }
+}
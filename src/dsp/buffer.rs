@@ -0,0 +1,65 @@
+use std::ops::{Add, Sub};
+use ::num_traits::Float;
+
+/// Truncates or zero pads the samples to exactly `len` samples.
+///
+/// ```
+/// use worthe_signals::dsp::fit_length;
+///
+/// assert_eq!(fit_length(&[1.0 as f32, 2.0, 3.0], 5), vec![1.0, 2.0, 3.0, 0.0, 0.0]);
+/// assert_eq!(fit_length(&[1.0 as f32, 2.0, 3.0], 2), vec![1.0, 2.0]);
+/// ```
+pub fn fit_length<T>(samples: &[T], len: usize) -> Vec<T> where T: Float {
+    let mut result = samples[..len.min(samples.len())].to_vec();
+    result.resize(len, T::zero());
+    result
+}
+
+/// The running sums of the data, so each output is the sum of all of
+/// the inputs up to and including the same index.
+///
+/// This works for anything that can be added, so the same function
+/// integrates real samples and complex samples.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::cumsum;
+///
+/// let ones = [Complex::new(1.0 as f32, 0.0); 3];
+/// let sums: Vec<f32> = cumsum(&ones).iter().map(|x| x.real).collect();
+/// assert_eq!(sums, vec![1.0, 2.0, 3.0]);
+///
+/// assert_eq!(cumsum(&[1, 2, 3, 4]), vec![1, 3, 6, 10]);
+/// ```
+pub fn cumsum<T>(data: &[T]) -> Vec<T> where T: Add<Output = T> + Copy {
+    let mut result: Vec<T> = Vec::with_capacity(data.len());
+    for &x in data {
+        let sum = match result.last() {
+            Some(&previous) => previous + x,
+            None => x
+        };
+        result.push(sum);
+    }
+    result
+}
+
+/// The differences between consecutive values,
+/// `out[i] = data[i+1] - data[i]`, which has one fewer value than the
+/// data (or none, for empty data).
+///
+/// This is the discrete derivative, and undoes `cumsum` apart from
+/// the first value. Like `cumsum`, it works on both real and complex
+/// sequences.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::{diff, cumsum};
+///
+/// assert_eq!(diff(&[1.0 as f32, 3.0, 6.0, 10.0]), vec![2.0, 3.0, 4.0]);
+///
+/// let x = [Complex::new(1, 2), Complex::new(-3, 0), Complex::new(4, 4)];
+/// assert_eq!(diff(&cumsum(&x)), x[1..].to_vec());
+/// ```
+pub fn diff<T>(data: &[T]) -> Vec<T> where T: Sub<Output = T> + Copy {
+    data.windows(2).map(|pair| pair[1] - pair[0]).collect()
+}
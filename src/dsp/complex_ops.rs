@@ -0,0 +1,185 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+
+/// Multiplies two sequences of complex numbers together, element by
+/// element. In the frequency domain, this is how a spectrum is
+/// filtered.
+///
+/// # Panics
+///
+/// Panics if the two sequences have different lengths.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::elementwise_mul;
+///
+/// let a = [Complex::new(1.0 as f32, 1.0), Complex::new(2.0, 0.0)];
+/// let b = [Complex::new(1.0 as f32, -1.0), Complex::new(0.0, 3.0)];
+/// assert_eq!(elementwise_mul(&a, &b), vec![Complex::new(2.0, 0.0), Complex::new(0.0, 6.0)]);
+/// ```
+pub fn elementwise_mul<T>(a: &[Complex<T>], b: &[Complex<T>]) -> Vec<Complex<T>> where T: Float {
+    assert_eq!(a.len(), b.len(), "Elementwise multiplication needs sequences of the same length");
+    a.iter().zip(b.iter()).map(|(&x, &y)| x*y).collect()
+}
+
+/// Adds two sequences of complex numbers together, element by
+/// element.
+///
+/// # Panics
+///
+/// Panics if the two sequences have different lengths.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::elementwise_add;
+///
+/// let a = [Complex::new(1.0 as f32, 1.0), Complex::new(2.0, 0.0)];
+/// let b = [Complex::new(1.0 as f32, -1.0), Complex::new(0.0, 3.0)];
+/// assert_eq!(elementwise_add(&a, &b), vec![Complex::new(2.0, 0.0), Complex::new(2.0, 3.0)]);
+/// ```
+pub fn elementwise_add<T>(a: &[Complex<T>], b: &[Complex<T>]) -> Vec<Complex<T>> where T: Float {
+    assert_eq!(a.len(), b.len(), "Elementwise addition needs sequences of the same length");
+    a.iter().zip(b.iter()).map(|(&x, &y)| x+y).collect()
+}
+
+/// The average of `a[n]·conj(b[n])`, which is the complex
+/// cross-correlation of two sequences at zero lag, normalized by
+/// their length.
+///
+/// Its angle is the average phase of `a` relative to `b`, and its
+/// magnitude is how strongly they are related, up to the average
+/// power of each. The correlation of empty sequences is 0.
+///
+/// # Panics
+///
+/// Panics if the two sequences have different lengths.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::complex_correlation;
+///
+/// let a = [Complex::new(0.0 as f32, 2.0), Complex::new(2.0, 0.0)];
+/// let b = [Complex::new(1.0 as f32, 0.0), Complex::new(0.0, -1.0)];
+/// assert_eq!(complex_correlation(&a, &b), Complex::new(0.0, 2.0));
+/// ```
+pub fn complex_correlation<T>(a: &[Complex<T>], b: &[Complex<T>]) -> Complex<T> where T: Float {
+    assert_eq!(a.len(), b.len(), "Complex correlation needs sequences of the same length");
+    if a.is_empty() {
+        return Complex::new(T::zero(), T::zero());
+    }
+    let sum = a.iter().zip(b.iter())
+        .fold(Complex::new(T::zero(), T::zero()), |acc, (&x, &y)| acc + x*y.conjugate());
+    let len = T::from_usize(a.len());
+    Complex::new(sum.real/len, sum.imag/len)
+}
+
+/// The ways that interleaved data can fail to pair up into complex
+/// numbers.
+#[derive(Debug, PartialEq)]
+pub enum InterleavedError {
+    /// The data had an odd length, so its last real part has no
+    /// imaginary part.
+    OddLength
+}
+
+/// Pairs up alternating real and imaginary parts,
+/// `[re0, im0, re1, im1, ...]`, into complex numbers.
+///
+/// This is how FFTW and many hardware buffers lay out complex data.
+///
+/// # Errors
+///
+/// The data needs to have an even length, since the last real part
+/// would otherwise have no imaginary part.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::{from_interleaved, InterleavedError};
+///
+/// assert_eq!(from_interleaved(&[1, 2, 3, 4]), Ok(vec![Complex::new(1, 2), Complex::new(3, 4)]));
+/// assert_eq!(from_interleaved(&[1, 2, 3]), Err(InterleavedError::OddLength));
+/// ```
+pub fn from_interleaved<T>(data: &[T]) -> Result<Vec<Complex<T>>, InterleavedError> where T: Copy {
+    if data.len() % 2 == 1 {
+        return Err(InterleavedError::OddLength);
+    }
+    Ok(data.chunks(2).map(|pair| Complex::new(pair[0], pair[1])).collect())
+}
+
+/// Splits complex numbers into alternating real and imaginary parts,
+/// `[re0, im0, re1, im1, ...]`. This is the inverse of
+/// `from_interleaved`.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::to_interleaved;
+///
+/// assert_eq!(to_interleaved(&[Complex::new(1, 2), Complex::new(3, 4)]), vec![1, 2, 3, 4]);
+/// ```
+pub fn to_interleaved<T>(data: &[Complex<T>]) -> Vec<T> where T: Copy {
+    data.iter().flat_map(|x| x.into_array()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+
+    #[test]
+    fn elementwise_ops_on_small_inputs() {
+        let a = vec![Complex::new(1.0, 2.0), Complex::new(-1.0, 0.5), Complex::new(0.0, 0.0)];
+        let b = vec![Complex::new(3.0, -1.0), Complex::new(2.0, 2.0), Complex::new(4.0, 4.0)];
+        assert_eq!(elementwise_mul(&a, &b), vec![Complex::new(5.0, 5.0), Complex::new(-3.0, -1.0), Complex::new(0.0, 0.0)]);
+        assert_eq!(elementwise_add(&a, &b), vec![Complex::new(4.0, 1.0), Complex::new(1.0, 2.5), Complex::new(4.0, 4.0)]);
+
+        let empty: Vec<Complex<f64>> = Vec::new();
+        assert!(elementwise_mul(&empty, &empty).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn elementwise_mul_rejects_mismatched_lengths() {
+        elementwise_mul(&[Complex::new(1.0, 0.0)], &[Complex::new(1.0, 0.0), Complex::new(2.0, 0.0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn elementwise_add_rejects_mismatched_lengths() {
+        elementwise_add(&[Complex::new(1.0, 0.0)], &[]);
+    }
+
+    #[test]
+    fn interleaved_round_trip() {
+        let interleaved = [0.5, -1.0, 2.0, 0.25, -3.0, 4.5];
+        let complex = from_interleaved(&interleaved).unwrap();
+        assert_eq!(complex, vec![Complex::new(0.5, -1.0), Complex::new(2.0, 0.25), Complex::new(-3.0, 4.5)]);
+        assert_eq!(to_interleaved(&complex), interleaved.to_vec());
+
+        let empty: Vec<f64> = Vec::new();
+        assert_eq!(from_interleaved(&empty), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn from_interleaved_rejects_odd_length() {
+        assert_eq!(from_interleaved(&[1.0, 2.0, 3.0]), Err(InterleavedError::OddLength));
+    }
+
+    #[test]
+    fn complex_correlation_of_rotated_copy() {
+        use ::dsp::noise;
+        let real: Vec<f64> = noise::white(64, 1.0, 8);
+        let imag: Vec<f64> = noise::white(64, 1.0, 9);
+        let a: Vec<Complex<f64>> = real.into_iter().zip(imag).map(|(r, i)| Complex::new(r, i)).collect();
+
+        let power = a.iter().map(|x| x.magnitude().powi(2)).sum::<f64>() / 64.0;
+        let with_itself = complex_correlation(&a, &a);
+        assert!(with_itself.real.approx_eq(power));
+        assert!(with_itself.imag.approx_eq(0.0));
+
+        let rotation = Complex::from_polar(1.0, -0.8);
+        let b: Vec<Complex<f64>> = a.iter().map(|&x| x*rotation).collect();
+        let rotated = complex_correlation(&a, &b);
+        assert!(rotated.angle().approx_eq(0.8));
+        assert!(rotated.magnitude().approx_eq(power));
+    }
+}
@@ -0,0 +1,288 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+use super::{fft, ifft};
+
+/// The full linear convolution of two sequences.
+///
+/// The result has `a.len() + b.len() - 1` samples, or is empty if
+/// either input is empty.
+///
+/// ```
+/// use worthe_signals::dsp::convolve;
+///
+/// assert_eq!(convolve(&[1.0 as f32, 2.0, 3.0], &[0.0, 1.0, 0.5]), vec![0.0, 1.0, 2.5, 4.0, 1.5]);
+/// ```
+pub fn convolve<T>(a: &[T], b: &[T]) -> Vec<T> where T: Float {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![T::zero(); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            result[i+j] = result[i+j] + x*y;
+        }
+    }
+    result
+}
+
+/// How much of a convolution `convolve_mode` keeps, following the
+/// same conventions as numpy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConvMode {
+    /// Every sample where the inputs overlap at all, the same as
+    /// `convolve`.
+    Full,
+    /// The middle of the full convolution, with the same length as
+    /// the longer input.
+    Same,
+    /// Only the samples where the shorter input overlaps the longer
+    /// one completely, so there are no edge effects.
+    Valid
+}
+
+/// The linear convolution of two sequences, trimmed according to
+/// `mode`.
+///
+/// With `m` and `n` as the longer and shorter input lengths, the
+/// result has `m + n - 1` samples for `Full`, `m` samples for `Same`
+/// and `m - n + 1` samples for `Valid`. It is empty if either input
+/// is empty.
+///
+/// ```
+/// use worthe_signals::dsp::{convolve_mode, ConvMode};
+///
+/// let a = [1.0 as f32, 2.0, 3.0];
+/// let b = [0.0, 1.0, 0.5];
+/// assert_eq!(convolve_mode(&a, &b, ConvMode::Full), vec![0.0, 1.0, 2.5, 4.0, 1.5]);
+/// assert_eq!(convolve_mode(&a, &b, ConvMode::Same), vec![1.0, 2.5, 4.0]);
+/// assert_eq!(convolve_mode(&a, &b, ConvMode::Valid), vec![2.5]);
+/// ```
+pub fn convolve_mode<T>(a: &[T], b: &[T], mode: ConvMode) -> Vec<T> where T: Float {
+    let full = convolve(a, b);
+    if full.is_empty() {
+        return full;
+    }
+    let longer = a.len().max(b.len());
+    let shorter = a.len().min(b.len());
+    let (start, len) = match mode {
+        ConvMode::Full => (0, full.len()),
+        ConvMode::Same => ((shorter - 1) / 2, longer),
+        ConvMode::Valid => (shorter - 1, longer - shorter + 1)
+    };
+    full[start..start + len].to_vec()
+}
+
+/// The full cross-correlation of two sequences, `r[lag] = Σ a[n+lag]·b[n]`.
+///
+/// Every lag where the sequences overlap is included, from
+/// `-(b.len()-1)` up to `a.len()-1`, so index `i` of the result is the
+/// lag `i - (b.len()-1)`. The result is empty if either input is
+/// empty.
+///
+/// ```
+/// use worthe_signals::dsp::cross_correlate;
+///
+/// // b lines up best with a when shifted one sample along
+/// let r = cross_correlate(&[0.0 as f32, 1.0, 2.0, 0.0], &[1.0, 2.0]);
+/// assert_eq!(r, vec![0.0, 2.0, 5.0, 2.0, 0.0]);
+/// ```
+pub fn cross_correlate<T>(a: &[T], b: &[T]) -> Vec<T> where T: Float {
+    let reversed: Vec<T> = b.iter().rev().cloned().collect();
+    convolve(a, &reversed)
+}
+
+/// The autocorrelation of a sequence at each non-negative lag,
+/// `r[lag] = Σ x[n]·x[n+lag]`.
+///
+/// The result has the same length as the input. It isn't normalized,
+/// so `r[0]` is the energy of the sequence, and longer lags have fewer
+/// overlapping samples to add up.
+///
+/// ```
+/// use worthe_signals::dsp::autocorrelate;
+///
+/// assert_eq!(autocorrelate(&[1.0 as f32, 2.0, 3.0]), vec![14.0, 8.0, 3.0]);
+/// ```
+pub fn autocorrelate<T>(samples: &[T]) -> Vec<T> where T: Float {
+    (0..samples.len()).map(|lag| {
+        samples.iter().zip(samples[lag..].iter())
+            .fold(T::zero(), |acc, (&a, &b)| acc + a*b)
+    }).collect()
+}
+
+/// Detects a known template in a signal.
+///
+/// This correlates the signal with the template, which is the same as
+/// filtering it with the time reversed template, and is the optimal
+/// linear detector for a known waveform in white noise. Index `lag` of
+/// the result is `Σ signal[lag+m]·template[m]`, the response with the
+/// template starting at that sample. Only lags where the whole
+/// template fits inside the signal are included, so the result has
+/// `signal.len() - template.len() + 1` samples, or is empty if the
+/// template is longer than the signal.
+///
+/// ```
+/// use worthe_signals::dsp::matched_filter;
+///
+/// let detection = matched_filter(&[0.0 as f32, 0.0, 1.0, -1.0, 0.0], &[1.0, -1.0]);
+/// assert_eq!(detection, vec![0.0, -1.0, 2.0, -1.0]);
+/// ```
+pub fn matched_filter<T>(signal: &[T], template: &[T]) -> Vec<T> where T: Float {
+    if template.is_empty() || template.len() > signal.len() {
+        return Vec::new();
+    }
+    let correlation = cross_correlate(signal, template);
+    let zero_lag = template.len() - 1;
+    correlation[zero_lag..zero_lag + signal.len() - template.len() + 1].to_vec()
+}
+
+/// A streaming FIR filter, using FFT convolution with the overlap
+/// save method.
+///
+/// Each block of input is transformed along with the last
+/// `taps.len() - 1` samples before it, so the filter picks up where
+/// the previous block left off. The outputs of consecutive calls to
+/// `process_block` join up into the same samples as convolving the
+/// whole stream at once, cut off at the length of the input so far.
+///
+/// The FFT size is the smallest power of two that fits a block plus
+/// the filter's history, so longer blocks make better use of it.
+///
+/// ```
+/// use worthe_signals::dsp::OverlapSave;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let mut filter = OverlapSave::new(&[0.5 as f64, 0.5], 4);
+/// let output = filter.process_block(&[2.0, 4.0, 6.0]);
+/// for (&y, &expected) in output.iter().zip(&[1.0, 3.0, 5.0]) {
+///     assert!(y.approx_eq(expected));
+/// }
+/// let output = filter.process_block(&[8.0]);
+/// assert!(output[0].approx_eq(7.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapSave<T> {
+    block_size: usize,
+    fft_size: usize,
+    taps_len: usize,
+    taps_spectrum: Vec<Complex<T>>,
+    history: Vec<T>
+}
+
+impl<T> OverlapSave<T> where T: Float {
+    /// # Panics
+    ///
+    /// Panics if there are no taps, or the block size is 0.
+    pub fn new(taps: &[T], block_size: usize) -> OverlapSave<T> {
+        assert!(!taps.is_empty(), "An overlap save filter needs at least one tap");
+        assert!(block_size > 0, "The block size can't be 0");
+        let fft_size = (block_size + taps.len() - 1).next_power_of_two();
+        let padded: Vec<Complex<T>> = (0..fft_size)
+            .map(|i| Complex::new(taps.get(i).cloned().unwrap_or_else(T::zero), T::zero()))
+            .collect();
+        OverlapSave {
+            block_size,
+            fft_size,
+            taps_len: taps.len(),
+            taps_spectrum: fft(&padded).expect("padded to a power of two"),
+            history: vec![T::zero(); taps.len() - 1]
+        }
+    }
+
+    /// Filters the next samples of the stream, giving the same number
+    /// of samples out. Input longer than the block size is processed
+    /// one block at a time.
+    pub fn process_block(&mut self, input: &[T]) -> Vec<T> {
+        let mut output = Vec::with_capacity(input.len());
+        for block in input.chunks(self.block_size) {
+            let mut frame: Vec<Complex<T>> = self.history.iter().chain(block.iter())
+                .map(|&x| Complex::new(x, T::zero()))
+                .collect();
+            frame.resize(self.fft_size, Complex::new(T::zero(), T::zero()));
+
+            let spectrum = fft(&frame).expect("padded to a power of two");
+            let product: Vec<Complex<T>> = spectrum.into_iter().zip(self.taps_spectrum.iter())
+                .map(|(x, &h)| x*h)
+                .collect();
+            let filtered = ifft(&product).expect("padded to a power of two");
+            let valid_start = self.taps_len - 1;
+            output.extend(filtered[valid_start..valid_start + block.len()].iter().map(|x| x.real));
+
+            let total = self.history.len() + block.len();
+            let keep: Vec<T> = self.history.iter().chain(block.iter())
+                .skip(total - (self.taps_len - 1))
+                .cloned()
+                .collect();
+            self.history = keep;
+        }
+        output
+    }
+
+    /// Clears the filter's memory of previous samples.
+    pub fn reset(&mut self) {
+        for x in self.history.iter_mut() {
+            *x = T::zero();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::dsp::noise;
+
+    #[test]
+    fn matched_filter_finds_template() {
+        let template = vec![1.0, -1.0, 1.0, 1.0, -1.0, 1.0, -1.0, -1.0];
+        let offset = 137;
+        let mut signal: Vec<f64> = noise::white(400, 0.3, 7);
+        for (i, &t) in template.iter().enumerate() {
+            signal[offset + i] += t;
+        }
+
+        let detection = matched_filter(&signal, &template);
+        assert_eq!(detection.len(), 400 - template.len() + 1);
+        let peak = detection.iter().enumerate()
+            .fold(0, |best, (i, &d)| if d > detection[best] { i } else { best });
+        assert_eq!(peak, offset);
+    }
+
+    #[test]
+    fn convolve_mode_lengths() {
+        let a: Vec<f64> = (0..10).map(|n| n as f64).collect();
+        let b = [1.0, -2.0, 0.5, 3.0];
+        for &(x, y) in &[(&a[..], &b[..]), (&b[..], &a[..])] {
+            assert_eq!(convolve_mode(x, y, ConvMode::Full), convolve(x, y));
+            assert_eq!(convolve_mode(x, y, ConvMode::Full).len(), 13);
+            assert_eq!(convolve_mode(x, y, ConvMode::Same).len(), 10);
+            assert_eq!(convolve_mode(x, y, ConvMode::Valid).len(), 7);
+        }
+        assert_eq!(convolve_mode(&[1.0, 2.0, 3.0, 4.0], &[1.0, 1.0], ConvMode::Same), vec![1.0, 3.0, 5.0, 7.0]);
+        assert!(convolve_mode(&a, &[], ConvMode::Valid).is_empty());
+    }
+
+    #[test]
+    fn overlap_save_matches_direct_convolution() {
+        let taps: Vec<f64> = noise::white(13, 1.0, 21);
+        let input: Vec<f64> = noise::white(300, 1.0, 22);
+        let expected = convolve(&input, &taps);
+
+        let mut filter = OverlapSave::new(&taps, 32);
+        let mut output = Vec::new();
+        let mut start = 0;
+        for &len in &[7, 32, 100, 1, 60, 100] {
+            output.extend(filter.process_block(&input[start..start + len]));
+            start += len;
+        }
+        assert_eq!(output.len(), input.len());
+        for (n, (a, b)) in output.iter().zip(expected.iter()).enumerate() {
+            assert!((a - b).abs() < 1e-9, "Sample {} was {}, expected {}", n, a, b);
+        }
+
+        filter.reset();
+        let restarted = filter.process_block(&input[..20]);
+        for (a, b) in restarted.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use ::num_traits::Float;
+
+/// Converts an amplitude ratio to decibels, `20·log10(a)`.
+///
+/// An amplitude of zero gives negative infinity, and negative
+/// amplitudes give NaN, following the floating point logarithm.
+///
+/// ```
+/// use worthe_signals::dsp::db::amplitude_to_db;
+///
+/// assert!((amplitude_to_db(10.0 as f32) - 20.0).abs() < 1e-5);
+/// assert_eq!(amplitude_to_db(0.0 as f32), -std::f32::INFINITY);
+/// ```
+pub fn amplitude_to_db<T>(a: T) -> T where T: Float {
+    T::from_f64(20.0) * a.log10()
+}
+
+/// Converts decibels to an amplitude ratio, the inverse of
+/// `amplitude_to_db`.
+///
+/// ```
+/// use worthe_signals::dsp::db::db_to_amplitude;
+///
+/// assert!((db_to_amplitude(20.0 as f32) - 10.0).abs() < 1e-5);
+/// assert!((db_to_amplitude(-6.0206 as f32) - 0.5).abs() < 1e-5);
+/// ```
+pub fn db_to_amplitude<T>(db: T) -> T where T: Float {
+    T::from_f64(10.0).powf(db / T::from_f64(20.0))
+}
+
+/// Converts a power ratio to decibels, `10·log10(p)`.
+///
+/// A power of zero gives negative infinity, and negative powers give
+/// NaN, following the floating point logarithm.
+///
+/// ```
+/// use worthe_signals::dsp::db::power_to_db;
+///
+/// assert!((power_to_db(2.0 as f32) - 3.0103).abs() < 1e-4);
+/// assert!((power_to_db(100.0 as f32) - 20.0).abs() < 1e-5);
+/// ```
+pub fn power_to_db<T>(p: T) -> T where T: Float {
+    T::from_f64(10.0) * p.log10()
+}
+
+/// Converts decibels to a power ratio, the inverse of `power_to_db`.
+///
+/// ```
+/// use worthe_signals::dsp::db::power_from_db;
+///
+/// assert!((power_from_db(3.0103 as f32) - 2.0).abs() < 1e-4);
+/// ```
+pub fn power_from_db<T>(db: T) -> T where T: Float {
+    T::from_f64(10.0).powf(db / T::from_f64(10.0))
+}
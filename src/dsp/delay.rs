@@ -0,0 +1,190 @@
+use ::num_traits::Float;
+
+/// Delays the samples by a number of samples that doesn't need to be a
+/// whole number.
+///
+/// The signal is interpolated between samples using a sinc kernel,
+/// tapered by a Hann window to 16 samples either side. The signal is
+/// treated as being zero outside of the given samples, so the first
+/// and last 16 or so output samples are affected by that edge.
+/// The output has the same length as the input.
+///
+/// # Panics
+///
+/// Panics if the delay is negative.
+///
+/// ```
+/// use worthe_signals::dsp::fractional_delay;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// // whole number delays are just a shift
+/// let delayed = fractional_delay(&[1.0 as f64, 2.0, 3.0, 4.0], 1.0);
+/// assert!(delayed.iter().zip(&[0.0, 1.0, 2.0, 3.0]).all(|(a, &b)| a.approx_eq(b)));
+/// ```
+pub fn fractional_delay<T>(samples: &[T], delay: T) -> Vec<T> where T: Float {
+    assert!(delay >= T::zero(), "Fractional delay must not be negative");
+    let half_width: isize = 16;
+
+    let whole = delay.to_usize();
+    let fraction = delay - T::from_usize(whole);
+
+    // kernel[i] is the weight given to the input sample (i - half_width + 1)
+    // samples before the whole number delay.
+    let kernel: Vec<T> = (0..2*half_width as usize).map(|i| {
+        let x = T::from_usize(i) - T::from_usize(half_width as usize - 1) - fraction;
+        let sinc = if x == T::zero() { T::one() } else { (T::pi()*x).sin() / (T::pi()*x) };
+        let half = T::from_f64(0.5);
+        let window = half + half*(T::pi()*x/T::from_usize(half_width as usize)).cos();
+        sinc*window
+    }).collect();
+    let gain = kernel.iter().fold(T::zero(), |acc, &k| acc + k);
+
+    let len = samples.len() as isize;
+    (0..len).map(|n| {
+        kernel.iter().enumerate().fold(T::zero(), |acc, (i, &weight)| {
+            let m = i as isize - half_width + 1;
+            let k = n - whole as isize - m;
+            if k >= 0 && k < len {
+                acc + samples[k as usize]*weight
+            } else {
+                acc
+            }
+        }) / gain
+    }).collect()
+}
+
+/// A ring buffer holding the most recent samples of a stream, for
+/// building delay effects out of.
+///
+/// `tap(0)` is the most recently pushed sample, `tap(1)` the one
+/// before it, and so on back to `tap(max_delay)`. The line starts out
+/// full of zeros, so tapping further back than has been pushed gives
+/// 0.
+///
+/// ```
+/// use worthe_signals::dsp::DelayLine;
+///
+/// let mut line = DelayLine::new(4);
+/// for x in 1..6 {
+///     line.push(x as f32);
+/// }
+/// assert_eq!(line.tap(0), 5.0);
+/// assert_eq!(line.tap(4), 1.0);
+/// assert_eq!(line.tap_fractional(1.5), 3.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelayLine<T> {
+    buffer: Vec<T>,
+    newest: usize
+}
+
+impl<T> DelayLine<T> where T: Float {
+    pub fn new(max_delay: usize) -> DelayLine<T> {
+        DelayLine {
+            buffer: vec![T::zero(); max_delay + 1],
+            newest: 0
+        }
+    }
+
+    /// The longest delay that can be tapped.
+    pub fn max_delay(&self) -> usize {
+        self.buffer.len() - 1
+    }
+
+    /// Adds a sample to the line, dropping the oldest one.
+    pub fn push(&mut self, x: T) {
+        self.newest = (self.newest + 1) % self.buffer.len();
+        self.buffer[self.newest] = x;
+    }
+
+    /// The sample pushed `delay` pushes ago.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delay` is more than `max_delay`.
+    pub fn tap(&self, delay: usize) -> T {
+        assert!(delay <= self.max_delay(), "Delay {} is longer than the line's {}", delay, self.max_delay());
+        let len = self.buffer.len();
+        self.buffer[(self.newest + len - delay) % len]
+    }
+
+    /// Taps the line between samples, linearly interpolating between
+    /// the two nearest whole number delays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delay` is negative or more than `max_delay`.
+    pub fn tap_fractional(&self, delay: T) -> T {
+        assert!(delay >= T::zero(), "Delay must not be negative");
+        let whole = delay.to_usize();
+        let fraction = delay - T::from_usize(whole);
+        if fraction == T::zero() {
+            return self.tap(whole);
+        }
+        let near = self.tap(whole);
+        let far = self.tap(whole + 1);
+        near + (far - near)*fraction
+    }
+
+    /// Fills the line with zeros again.
+    pub fn reset(&mut self) {
+        for x in self.buffer.iter_mut() {
+            *x = T::zero();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+    use ::sinusoid::Sinusoid;
+
+    #[test]
+    fn half_sample_delay_of_sinusoid() {
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 0.05, 0.3);
+        let samples = tone.sample_range(0.0, 256.0, 1.0);
+        let delayed = fractional_delay(&samples, 0.5);
+        assert_eq!(delayed.len(), samples.len());
+        for (n, &y) in delayed.iter().enumerate().take(224).skip(32) {
+            let expected = tone.sample(n as f64 - 0.5);
+            assert!((y - expected).abs() < 1e-3, "Sample {} was {}, expected {}", n, y, expected);
+        }
+    }
+
+    #[test]
+    fn delay_with_whole_and_fractional_parts() {
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 0.03, 0.0);
+        let samples = tone.sample_range(0.0, 256.0, 1.0);
+        let delayed = fractional_delay(&samples, 3.25);
+        for (n, &y) in delayed.iter().enumerate().take(224).skip(32) {
+            let expected = tone.sample(n as f64 - 3.25);
+            assert!((y - expected).abs() < 1e-3, "Sample {} was {}, expected {}", n, y, expected);
+        }
+    }
+
+    #[test]
+    fn delay_line_taps_past_samples() {
+        let mut line: DelayLine<f64> = DelayLine::new(8);
+        assert_eq!(line.tap(8), 0.0);
+        for n in 0..20 {
+            line.push(n as f64);
+        }
+        for k in 0..9 {
+            assert_eq!(line.tap(k), 19.0 - k as f64);
+        }
+        assert!(line.tap_fractional(2.25).approx_eq(16.75));
+        assert!(line.tap_fractional(7.5).approx_eq(11.5));
+        assert_eq!(line.tap_fractional(8.0), 11.0);
+
+        line.reset();
+        assert_eq!(line.tap(0), 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn delay_line_rejects_long_taps() {
+        let line: DelayLine<f64> = DelayLine::new(8);
+        line.tap_fractional(8.5);
+    }
+}
@@ -0,0 +1,463 @@
+use std::cmp::Ordering;
+use ::complex::Complex;
+use ::num_traits::Float;
+use super::analytic_signal;
+
+/// Hard clips the samples, limiting each one to the range
+/// [-threshold, threshold].
+///
+/// This models an amplifier being overdriven. The flattened peaks
+/// introduce harmonics that weren't in the original signal, which
+/// increases its total harmonic distortion.
+///
+/// ```
+/// use worthe_signals::dsp::clip;
+///
+/// assert_eq!(clip(&[0.5 as f32, 2.0, -3.0, -0.25], 1.0), vec![0.5, 1.0, -1.0, -0.25]);
+/// ```
+pub fn clip<T>(samples: &[T], threshold: T) -> Vec<T> where T: Float {
+    samples.iter().map(|&x| {
+        if x > threshold {
+            threshold
+        } else if x < -threshold {
+            -threshold
+        } else {
+            x
+        }
+    }).collect()
+}
+
+/// Soft clips the samples with `threshold·tanh(x/threshold)`.
+///
+/// Small samples pass through almost unchanged, and large samples are
+/// smoothly squashed towards ±threshold instead of being cut off
+/// abruptly. Like hard clipping, this introduces harmonics, but with
+/// less energy in the higher harmonics.
+///
+/// ```
+/// use worthe_signals::dsp::soft_clip;
+///
+/// let clipped = soft_clip(&[0.01 as f32, 100.0], 1.0);
+/// assert!((clipped[0] - 0.01).abs() < 1e-5);
+/// assert!((clipped[1] - 1.0).abs() < 1e-5);
+/// ```
+pub fn soft_clip<T>(samples: &[T], threshold: T) -> Vec<T> where T: Float {
+    samples.iter().map(|&x| threshold * (x/threshold).tanh()).collect()
+}
+
+/// Adds echoes to the samples with a feedback delay line.
+///
+/// The delay line holds the input delayed by `delay_samples`, plus its
+/// own output fed back with a gain of `feedback`. The output is the
+/// dry signal plus the delay line scaled by `mix`, so an impulse gives
+/// echoes every `delay_samples` samples, with amplitudes `mix`,
+/// `mix·feedback`, `mix·feedback²` and so on.
+///
+/// The echoes only decay if `|feedback| < 1`. Anything larger makes
+/// the delay line unstable, with each echo louder than the last. The
+/// output is the same length as the input, so any echoes after the
+/// end of the input are cut off.
+///
+/// # Panics
+///
+/// Panics if `delay_samples` is zero.
+///
+/// ```
+/// use worthe_signals::dsp::echo;
+///
+/// let out = echo(&[1.0 as f32, 0.0, 0.0, 0.0, 0.0], 2, 0.5, 1.0);
+/// assert_eq!(out, vec![1.0, 0.0, 1.0, 0.0, 0.5]);
+/// ```
+pub fn echo<T>(samples: &[T], delay_samples: usize, feedback: T, mix: T) -> Vec<T> where T: Float {
+    assert!(delay_samples > 0, "An echo needs a delay of at least one sample");
+    let mut delay_line = vec![T::zero(); samples.len()];
+    for n in delay_samples..samples.len() {
+        delay_line[n] = samples[n - delay_samples] + feedback*delay_line[n - delay_samples];
+    }
+    samples.iter().zip(delay_line)
+        .map(|(&dry, wet)| dry + mix*wet)
+        .collect()
+}
+
+/// A comb filter, which adds a copy of the signal delayed by
+/// `delay_samples`.
+///
+/// The feedforward form is `y[n] = x[n] + g·x[n-D]`, and the feedback
+/// form is `y[n] = x[n] + g·y[n-D]`. Either way, frequencies that are
+/// a multiple of `sample_rate/D` are reinforced, and the frequencies
+/// half way between them are attenuated, giving a frequency response
+/// that looks like the teeth of a comb.
+///
+/// The feedback form is only stable if `|gain| < 1`, otherwise the
+/// output grows without bound. The output is the same length as the
+/// input.
+///
+/// # Panics
+///
+/// Panics if `delay_samples` is zero.
+///
+/// ```
+/// use worthe_signals::dsp::comb_filter;
+///
+/// let impulse = [1.0 as f32, 0.0, 0.0, 0.0, 0.0];
+/// assert_eq!(comb_filter(&impulse, 2, 0.5, true), vec![1.0, 0.0, 0.5, 0.0, 0.0]);
+/// assert_eq!(comb_filter(&impulse, 2, 0.5, false), vec![1.0, 0.0, 0.5, 0.0, 0.25]);
+/// ```
+pub fn comb_filter<T>(samples: &[T], delay_samples: usize, gain: T, feedforward: bool) -> Vec<T> where T: Float {
+    assert!(delay_samples > 0, "A comb filter needs a delay of at least one sample");
+    let mut result = samples.to_vec();
+    for n in delay_samples..samples.len() {
+        let delayed = if feedforward {
+            samples[n - delay_samples]
+        } else {
+            result[n - delay_samples]
+        };
+        result[n] = samples[n] + gain*delayed;
+    }
+    result
+}
+
+/// Replaces each sample with the median of the `window` samples
+/// centred on it.
+///
+/// Unlike a linear filter, which smears an impulse out over its
+/// neighbours, a median filter removes isolated spikes completely
+/// while keeping sharp edges intact.
+///
+/// At the edges, the first and last samples are repeated to fill the
+/// window, so the output has the same length as the input.
+///
+/// # Panics
+///
+/// The window needs a middle sample, so this panics if `window` is
+/// even.
+///
+/// ```
+/// use worthe_signals::dsp::median_filter;
+///
+/// let filtered = median_filter(&[1.0 as f32, 2.0, 50.0, 4.0, 5.0], 3);
+/// assert_eq!(filtered, vec![1.0, 2.0, 4.0, 5.0, 5.0]);
+/// ```
+pub fn median_filter<T>(samples: &[T], window: usize) -> Vec<T> where T: Float {
+    assert!(window % 2 == 1, "The median filter window must be odd, but was {}", window);
+    if samples.is_empty() {
+        return Vec::new();
+    }
+
+    let half = window / 2;
+    let last = samples.len() - 1;
+    (0..samples.len()).map(|n| {
+        let mut neighbourhood: Vec<T> = (0..window)
+            .map(|i| samples[(n + i).saturating_sub(half).min(last)])
+            .collect();
+        neighbourhood.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        neighbourhood[half]
+    }).collect()
+}
+
+/// The shape of the gain curves used by `crossfade`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FadeCurve {
+    /// The gains add up to 1, which keeps the level steady when the
+    /// two inputs are the same (or strongly correlated) signal.
+    Linear,
+    /// The squares of the gains add up to 1, which keeps the power
+    /// steady when the two inputs are unrelated signals.
+    EqualPower
+}
+
+/// Joins `b` onto the end of `a`, fading out the last `fade_len`
+/// samples of `a` while fading in the first `fade_len` samples of
+/// `b` over the top of them.
+///
+/// The fade samples are spaced so that neither input is ever at full
+/// or zero gain inside the fade, which puts the middle of an odd
+/// length linear fade at exactly the average of the two inputs.
+///
+/// The fade can't be longer than either input, so a `fade_len` longer
+/// than the shorter input is cut down to that input's length. The
+/// output is `a.len() + b.len() - fade_len` samples long after that
+/// adjustment.
+///
+/// ```
+/// use worthe_signals::dsp::{crossfade, FadeCurve};
+///
+/// let joined = crossfade(&[1.0 as f32; 4], &[0.0; 4], 3, FadeCurve::Linear);
+/// assert_eq!(joined, vec![1.0, 0.75, 0.5, 0.25, 0.0]);
+/// ```
+pub fn crossfade<T>(a: &[T], b: &[T], fade_len: usize, curve: FadeCurve) -> Vec<T> where T: Float {
+    let fade_len = fade_len.min(a.len()).min(b.len());
+    let fade_start = a.len() - fade_len;
+
+    let mut result = Vec::with_capacity(a.len() + b.len() - fade_len);
+    result.extend_from_slice(&a[..fade_start]);
+    for i in 0..fade_len {
+        let t = T::from_usize(i + 1) / T::from_usize(fade_len + 1);
+        let (gain_a, gain_b) = match curve {
+            FadeCurve::Linear => (T::one() - t, t),
+            FadeCurve::EqualPower => ((t*T::half_pi()).cos(), (t*T::half_pi()).sin())
+        };
+        result.push(a[fade_start + i]*gain_a + b[i]*gain_b);
+    }
+    result.extend_from_slice(&b[fade_len..]);
+    result
+}
+
+/// Shapes the samples with a piecewise linear attack, decay, sustain,
+/// release (ADSR) envelope.
+///
+/// The envelope ramps up from 0 to reach 1 on the last of the
+/// `attack` samples, then falls to reach `sustain_level` on the last
+/// of the `decay` samples, and holds there until the last `release`
+/// samples, which ramp it down to reach 0 on the very last sample.
+///
+/// The release always finishes at the end of the buffer, like a key
+/// being let go, so if the buffer is too short for all of the
+/// segments the release starts from whatever level the attack or
+/// decay had reached by then. A release longer than the buffer starts
+/// from the first sample.
+///
+/// ```
+/// use worthe_signals::dsp::apply_adsr;
+///
+/// let shaped = apply_adsr(&[1.0 as f32; 8], 2, 2, 0.5, 2);
+/// assert_eq!(shaped, vec![0.5, 1.0, 0.75, 0.5, 0.5, 0.5, 0.25, 0.0]);
+/// ```
+pub fn apply_adsr<T>(samples: &[T], attack: usize, decay: usize, sustain_level: T, release: usize) -> Vec<T> where T: Float {
+    let ratio = |n: usize, len: usize| T::from_usize(n + 1) / T::from_usize(len);
+    let before_release = |n: usize| {
+        if n < attack {
+            ratio(n, attack)
+        } else if n < attack + decay {
+            T::one() - (T::one() - sustain_level)*ratio(n - attack, decay)
+        } else {
+            sustain_level
+        }
+    };
+
+    let release_start = samples.len().saturating_sub(release);
+    let release_len = samples.len() - release_start;
+    let release_level = before_release(release_start);
+    samples.iter().enumerate().map(|(n, &x)| {
+        let gain = if n < release_start {
+            before_release(n)
+        } else {
+            release_level*(T::one() - ratio(n - release_start, release_len))
+        };
+        x*gain
+    }).collect()
+}
+
+/// Shifts every frequency in the signal up by `shift_hz`, or down if
+/// it's negative.
+///
+/// Unlike pitch shifting, this adds the same number of hertz to every
+/// frequency rather than multiplying them, so harmonics stop being
+/// harmonic and a voice turns metallic. The signal's analytic signal
+/// is rotated by `e^(j2π·shift·t)`, and the real part is kept, which
+/// moves the positive frequencies without letting them mirror back
+/// like ring modulation would. Frequencies pushed below 0 Hz or past
+/// Nyquist wrap around.
+///
+/// The analytic signal treats the signal as though it repeats, so
+/// content near the ends can leak across. See `analytic_signal`.
+///
+/// ```
+/// use worthe_signals::dsp::frequency_shift;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// let tone = Sinusoid::new(1.0 as f64, 100.0, 0.0).sample_range(0.0, 1.0, 1024.0);
+/// let shifted = frequency_shift(&tone, 28.0, 1024.0);
+/// let expected = Sinusoid::new(1.0 as f64, 128.0, 0.0).sample_range(0.0, 1.0, 1024.0);
+/// for (a, b) in shifted.iter().zip(expected.iter()) {
+///     assert!((a - b).abs() < 1e-9);
+/// }
+/// ```
+pub fn frequency_shift<T>(samples: &[T], shift_hz: T, sample_rate: T) -> Vec<T> where T: Float {
+    let step = T::two_pi()*shift_hz/sample_rate;
+    analytic_signal(samples).into_iter().enumerate()
+        .map(|(n, z)| (z*Complex::from_polar(T::one(), step*T::from_usize(n))).real)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+    use ::sinusoid::Sinusoid;
+
+    /// Checks the comb's teeth, with a delay of 8 samples, using
+    /// the frequency response of its impulse response.
+    fn assert_comb_response(impulse_response: &[f64], peak: f64, trough: f64) {
+        use ::dsp::freq_response;
+
+        // 33 points puts a point every π/32 radians, so the peaks at
+        // multiples of 2π/8 are every 8 points.
+        let response = freq_response(impulse_response, 33);
+        for (k, h) in response.iter().enumerate() {
+            let magnitude = h.magnitude();
+            if k % 8 == 0 {
+                assert!((magnitude - peak).abs() < 1e-9, "Point {} was {}", k, magnitude);
+            } else if k % 8 == 4 {
+                assert!((magnitude - trough).abs() < 1e-9, "Point {} was {}", k, magnitude);
+            } else {
+                assert!(magnitude < peak && magnitude > trough, "Point {} was {}", k, magnitude);
+            }
+        }
+    }
+
+    #[test]
+    fn frequency_shift_moves_tone_up() {
+        use ::dsp::rfft;
+        let tone: Sinusoid<f64> = Sinusoid::new(0.5, 1000.0, 0.7);
+        let samples = tone.sample_count(0.0, 8192, 8192.0);
+        let shifted = frequency_shift(&samples, 200.0, 8192.0);
+        let spectrum = rfft(&shifted).unwrap();
+        let peak = spectrum.iter().enumerate()
+            .fold(0, |best, (k, x)| if x.magnitude() > spectrum[best].magnitude() { k } else { best });
+        // the bins are 1 Hz apart
+        assert_eq!(peak, 1200);
+        assert!((spectrum[1200].magnitude() - 0.5*4096.0).abs() < 1e-6);
+        assert!(spectrum[1000].magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn feedforward_comb_response() {
+        let mut impulse: Vec<f64> = vec![0.0; 16];
+        impulse[0] = 1.0;
+        let response = comb_filter(&impulse, 8, 0.9, true);
+        assert_comb_response(&response, 1.9, 0.1);
+    }
+
+    #[test]
+    fn feedback_comb_response() {
+        let mut impulse: Vec<f64> = vec![0.0; 800];
+        impulse[0] = 1.0;
+        let response = comb_filter(&impulse, 8, 0.5, false);
+        assert_comb_response(&response, 2.0, 2.0/3.0);
+    }
+
+    #[test]
+    fn echoes_decay_geometrically() {
+        let mut impulse: Vec<f64> = vec![0.0; 50];
+        impulse[0] = 1.0;
+        let out = echo(&impulse, 10, 0.5, 0.8);
+        assert_eq!(out.len(), 50);
+        for (n, &y) in out.iter().enumerate() {
+            let expected = match n {
+                0 => 1.0,
+                n if n % 10 == 0 => 0.8 * 0.5f64.powi(n as i32/10 - 1),
+                _ => 0.0
+            };
+            assert!(y.approx_eq(expected), "Sample {} was {}, expected {}", n, y, expected);
+        }
+    }
+
+    #[test]
+    fn median_filter_removes_spike_from_ramp() {
+        let mut ramp: Vec<f64> = (0..20).map(|n| n as f64 * 0.5).collect();
+        let clean = ramp.clone();
+        ramp[9] = 100.0;
+        let filtered = median_filter(&ramp, 5);
+        assert_eq!(filtered.len(), ramp.len());
+        // the spike's true value is lost, so near it the median lands
+        // within one step of the ramp. Away from it, and away from the
+        // repeated edge samples, the ramp is kept exactly.
+        for (n, (&f, &c)) in filtered.iter().zip(clean.iter()).enumerate() {
+            if (7..=11).contains(&n) {
+                assert!((f - c).abs() <= 0.5, "sample {}: {} vs {}", n, f, c);
+            } else if (2..18).contains(&n) {
+                assert_eq!(f, c);
+            }
+        }
+        assert_eq!(filtered[0], 0.0);
+        assert_eq!(filtered[19], 9.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn median_filter_needs_odd_window() {
+        median_filter(&[1.0, 2.0, 3.0], 4);
+    }
+
+    #[test]
+    fn hard_clipping_flattens_peaks() {
+        let loud: Sinusoid<f64> = Sinusoid::new(2.0, 1.0, 0.0);
+        let samples = loud.sample_range(0.0, 1.0, 100.0);
+        let clipped = clip(&samples, 1.0);
+
+        assert!(clipped.iter().all(|x| (-1.0..=1.0).contains(x)));
+        // 2cos(x) is more than 1 in magnitude for two thirds of the
+        // period, which is flattened.
+        let flat = clipped.iter().filter(|&&x| x == 1.0 || x == -1.0).count();
+        assert!((60..70).contains(&flat), "{} samples were clipped", flat);
+    }
+
+    #[test]
+    fn soft_clipping_is_monotonic_and_smooth() {
+        let ramp: Vec<f64> = (0..=400).map(|i| (i as f64 - 200.0) / 40.0).collect();
+        let clipped = soft_clip(&ramp, 1.0);
+
+        let steps: Vec<f64> = clipped.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        assert!(steps.iter().all(|&step| step > 0.0));
+        assert!(clipped.iter().all(|&x| x > -1.0 && x < 1.0));
+        // a smooth curve has no sudden changes in slope
+        for pair in steps.windows(2) {
+            assert!((pair[1] - pair[0]).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn linear_crossfade_averages_at_midpoint() {
+        let a: Vec<f64> = (0..10).map(|n| n as f64).collect();
+        let b: Vec<f64> = (0..8).map(|n| -(n as f64)).collect();
+        let joined = crossfade(&a, &b, 5, FadeCurve::Linear);
+        assert_eq!(joined.len(), 13);
+        assert_eq!(joined[..5], a[..5]);
+        assert_eq!(joined[10..], b[5..]);
+        assert!(joined[7].approx_eq((a[7] + b[2]) / 2.0));
+    }
+
+    #[test]
+    fn equal_power_crossfade_keeps_power() {
+        let fade_out = crossfade(&[1.0_f64; 6], &[0.0; 6], 4, FadeCurve::EqualPower);
+        let fade_in = crossfade(&[0.0_f64; 6], &[1.0; 6], 4, FadeCurve::EqualPower);
+        assert_eq!(fade_out.len(), 8);
+        for (gain_a, gain_b) in fade_out[2..6].iter().zip(fade_in[2..6].iter()) {
+            assert!((gain_a*gain_a + gain_b*gain_b).approx_eq(1.0));
+        }
+    }
+
+    #[test]
+    fn crossfade_longer_than_input_is_shortened() {
+        let joined = crossfade(&[1.0_f64, 1.0], &[0.0; 5], 10, FadeCurve::Linear);
+        assert_eq!(joined.len(), 5);
+        assert!(joined[0].approx_eq(2.0 / 3.0));
+        assert!(joined[1].approx_eq(1.0 / 3.0));
+    }
+
+    #[test]
+    fn adsr_envelope_segments() {
+        let envelope = apply_adsr(&[1.0_f64; 100], 10, 20, 0.6, 30);
+        assert_eq!(envelope.len(), 100);
+        assert!(envelope[..10].windows(2).all(|pair| pair[1] > pair[0]));
+        assert!(envelope[9].approx_eq(1.0));
+        assert!(envelope[10..30].windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(envelope[29..70].iter().all(|&x| x.approx_eq(0.6)));
+        assert!(envelope[70..].windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(envelope[99].approx_eq(0.0));
+    }
+
+    #[test]
+    fn adsr_release_cuts_short_attack() {
+        // the release starts halfway up the attack
+        let envelope = apply_adsr(&[1.0_f64; 6], 6, 2, 0.5, 3);
+        assert_eq!(envelope.len(), 6);
+        assert!(envelope[2].approx_eq(0.5));
+        assert!(envelope[3].approx_eq(4.0 / 6.0 * 2.0 / 3.0));
+        assert!(envelope[5].approx_eq(0.0));
+
+        let all_release = apply_adsr(&[1.0_f64; 4], 1, 0, 1.0, 10);
+        assert_eq!(all_release, vec![0.75, 0.5, 0.25, 0.0]);
+    }
+}
@@ -0,0 +1,260 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+use super::{convolve, unwrap_phase};
+
+/// Evaluates the frequency response of the taps at a single
+/// frequency, given in radians per sample.
+fn response_at<T>(taps: &[T], omega: T) -> Complex<T> where T: Float {
+    taps.iter().enumerate().fold(Complex::new(T::zero(), T::zero()), |acc, (n, &tap)| {
+        acc + Complex::from_polar(tap, -omega*T::from_usize(n))
+    })
+}
+
+/// Evenly spaced frequencies from 0 to π radians per sample, both
+/// inclusive.
+fn nyquist_grid<T>(num_points: usize) -> Vec<T> where T: Float {
+    let intervals = if num_points > 1 { num_points - 1 } else { 1 };
+    (0..num_points)
+        .map(|k| T::pi() * T::from_usize(k) / T::from_usize(intervals))
+        .collect()
+}
+
+/// Evaluates the frequency response, H(e^jω), of an FIR filter.
+///
+/// The response is evaluated at `num_points` evenly spaced
+/// frequencies from 0 to π radians per sample (Nyquist), both
+/// inclusive. This is equivalent to taking a zero padded DFT of the
+/// taps, keeping only the non-negative frequencies.
+///
+/// ```
+/// use worthe_signals::dsp::freq_response;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let response = freq_response(&[1.0 as f64], 8);
+/// assert_eq!(response.len(), 8);
+/// for h in response {
+///     assert!(h.magnitude().approx_eq(1.0));
+/// }
+/// ```
+pub fn freq_response<T>(taps: &[T], num_points: usize) -> Vec<Complex<T>> where T: Float {
+    nyquist_grid(num_points).into_iter()
+        .map(|omega| response_at(taps, omega))
+        .collect()
+}
+
+/// The group delay of an FIR filter is the negative derivative of
+/// its unwrapped phase response. It describes how many samples each
+/// frequency is delayed by when passing through the filter.
+///
+/// The delay is evaluated at `num_points` evenly spaced frequencies
+/// from 0 to Nyquist, inclusive. The derivative is approximated with
+/// central differences, falling back to one sided differences at the
+/// two ends.
+///
+/// # Panics
+///
+/// At least two points are needed to estimate a derivative, so this
+/// panics if `num_points` is less than 2.
+///
+/// ```
+/// use worthe_signals::dsp::group_delay;
+///
+/// // A single delayed impulse delays every frequency by the same
+/// // amount.
+/// let delay = group_delay(&[0.0, 0.0, 0.0, 1.0], 16);
+/// for d in delay {
+///     assert!((d - 3.0 as f64).abs() < 1e-9);
+/// }
+/// ```
+pub fn group_delay<T>(taps: &[T], num_points: usize) -> Vec<T> where T: Float {
+    assert!(num_points >= 2, "Group delay needs at least 2 points, but {} were requested", num_points);
+
+    let phase: Vec<T> = freq_response(taps, num_points).into_iter()
+        .map(|h| h.angle())
+        .collect();
+    let phase = unwrap_phase(&phase);
+
+    let step = T::pi() / T::from_usize(num_points - 1);
+    let last = num_points - 1;
+    (0..num_points).map(|k| {
+        let (before, after) = if k == 0 {
+            (0, 1)
+        } else if k == last {
+            (last - 1, last)
+        } else {
+            (k - 1, k + 1)
+        };
+        let width = T::from_usize(after - before) * step;
+        -(phase[after] - phase[before]) / width
+    }).collect()
+}
+
+/// Filters the samples forwards and then backwards, so that the phase
+/// shifts of the two passes cancel out.
+///
+/// The result has zero phase distortion, so features in the signal
+/// line up with the input, but the filter's magnitude response is
+/// applied twice. The output has the same length as the input.
+///
+/// The signal is treated as being zero outside of the given samples,
+/// so there are transients at both ends, each about as long as the
+/// taps, where the filter runs into that zero padding.
+///
+/// ```
+/// use worthe_signals::dsp::filtfilt;
+///
+/// let impulse = vec![0.0 as f64, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+/// let smoothed = filtfilt(&[0.5, 0.5], &impulse);
+/// assert_eq!(smoothed, vec![0.0, 0.0, 0.25, 0.5, 0.25, 0.0, 0.0]);
+/// ```
+pub fn filtfilt<T>(taps: &[T], samples: &[T]) -> Vec<T> where T: Float {
+    if taps.is_empty() || samples.is_empty() {
+        return vec![T::zero(); samples.len()];
+    }
+
+    let mut forward = convolve(taps, samples);
+    forward.reverse();
+    let mut result = convolve(taps, &forward);
+    result.reverse();
+
+    // each pass adds taps.len()-1 samples, one at each end after the
+    // reversal.
+    let edge = taps.len() - 1;
+    result[edge..edge+samples.len()].to_vec()
+}
+
+/// Scales the taps in place so that they sum to 1, giving the filter
+/// unity gain at DC.
+///
+/// Taps that sum to zero, like a high pass filter's, have no DC gain
+/// to normalize against, and are left unchanged.
+///
+/// ```
+/// use worthe_signals::dsp::fir::normalize_dc;
+///
+/// let mut taps = vec![1.0 as f32, 2.0, 1.0];
+/// normalize_dc(&mut taps);
+/// assert_eq!(taps, vec![0.25, 0.5, 0.25]);
+/// ```
+pub fn normalize_dc<T>(taps: &mut [T]) where T: Float {
+    let sum = taps.iter().fold(T::zero(), |acc, &tap| acc + tap);
+    scale(taps, sum);
+}
+
+/// Scales the taps in place so that the sum of their squares is 1,
+/// giving the filter unity gain for white noise.
+///
+/// Taps that are all zero have no energy to normalize against, and
+/// are left unchanged.
+///
+/// ```
+/// use worthe_signals::dsp::fir::normalize_energy;
+///
+/// let mut taps = vec![3.0 as f32, 4.0];
+/// normalize_energy(&mut taps);
+/// assert_eq!(taps, vec![0.6, 0.8]);
+/// ```
+pub fn normalize_energy<T>(taps: &mut [T]) where T: Float {
+    let energy = taps.iter().fold(T::zero(), |acc, &tap| acc + tap*tap);
+    scale(taps, energy.sqrt());
+}
+
+fn scale<T>(taps: &mut [T], divisor: T) where T: Float {
+    if divisor == T::zero() {
+        return;
+    }
+    for tap in taps.iter_mut() {
+        *tap = *tap / divisor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+    use std::f64::consts::PI;
+
+    /// A windowed sinc low pass filter with a cutoff of a quarter of
+    /// the sample rate.
+    fn low_pass_prototype() -> Vec<f64> {
+        let len = 21;
+        let middle = (len / 2) as f64;
+        (0..len).map(|n| {
+            let x = n as f64 - middle;
+            let sinc = if x == 0.0 { 0.5 } else { (0.5*PI*x).sin() / (PI*x) };
+            let hann = 0.5 - 0.5*(2.0*PI*n as f64 / (len - 1) as f64).cos();
+            sinc * hann
+        }).collect()
+    }
+
+    #[test]
+    fn normalize_dc_gives_unity_dc_gain() {
+        let mut taps = low_pass_prototype();
+        normalize_dc(&mut taps);
+        let dc_gain = freq_response(&taps, 2)[0];
+        assert!(dc_gain.real.approx_eq(1.0), "DC gain was {:?}", dc_gain);
+        assert!(dc_gain.imag.approx_eq(0.0), "DC gain was {:?}", dc_gain);
+    }
+
+    #[test]
+    fn normalize_energy_gives_unit_sum_of_squares() {
+        let mut taps = low_pass_prototype();
+        normalize_energy(&mut taps);
+        let energy: f64 = taps.iter().map(|t| t*t).sum();
+        assert!(energy.approx_eq(1.0), "Energy was {}", energy);
+    }
+
+    #[test]
+    fn normalize_leaves_zero_sums_unchanged() {
+        let mut taps = vec![1.0, -1.0];
+        normalize_dc(&mut taps);
+        assert_eq!(taps, vec![1.0, -1.0]);
+    }
+
+    #[test]
+    fn filtfilt_has_no_time_shift() {
+        let mut step: Vec<f64> = vec![0.0; 50];
+        step.extend(vec![1.0; 50]);
+        // deliberately asymmetric taps, which would shift the step if
+        // only applied once
+        let taps = vec![0.5, 0.3, 0.2];
+        let output = filtfilt(&taps, &step);
+        assert_eq!(output.len(), step.len());
+
+        // the step response is antisymmetric around the transition
+        // between samples 49 and 50
+        for k in 0..20 {
+            let sum = output[50+k] + output[49-k];
+            assert!(sum.approx_eq(1.0), "Samples {} and {} summed to {}", 50+k, 49-k, sum);
+        }
+        assert!(output[60].approx_eq(1.0));
+        assert!(output[40].approx_eq(0.0));
+    }
+
+    #[test]
+    fn averaging_filter_response() {
+        let response = freq_response(&[0.5_f64, 0.5], 32);
+        assert_eq!(response.len(), 32);
+
+        let dc = response[0];
+        assert!(dc.real.approx_eq(1.0));
+        assert!(dc.imag.approx_eq(0.0));
+
+        let nyquist = response[31];
+        assert!(nyquist.magnitude() < 1e-12);
+    }
+
+    #[test]
+    fn symmetric_fir_has_constant_group_delay() {
+        // symmetric taps give linear phase. These are chosen so that
+        // the response has no zeros on the unit circle, which would
+        // otherwise give a phase jump of π.
+        let taps: Vec<f64> = vec![1.0, 3.0, 5.0, 3.0, 1.0];
+        let expected = (taps.len() - 1) as f64 / 2.0;
+        let delay = group_delay(&taps, 64);
+        assert_eq!(delay.len(), 64);
+        for (i, d) in delay.iter().enumerate() {
+            assert!((d - expected).abs() < 1e-9, "Delay at point {} was {}", i, d);
+        }
+    }
+}
@@ -0,0 +1,587 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+
+/// The discrete Fourier transform.
+///
+/// This is the direct O(n²) evaluation of the transform, so it works
+/// for any length of input. No normalization is applied in the
+/// forward direction, so bin k is `Σ x[n]·e^(-j2πkn/N)`.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::dft;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let impulse = vec![Complex::new(1.0 as f64, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)];
+/// for bin in dft(&impulse) {
+///     assert!(bin.real.approx_eq(1.0));
+///     assert!(bin.imag.approx_eq(0.0));
+/// }
+/// ```
+pub fn dft<T>(samples: &[Complex<T>]) -> Vec<Complex<T>> where T: Float {
+    let len = samples.len();
+    (0..len).map(|k| {
+        samples.iter().enumerate().fold(Complex::new(T::zero(), T::zero()), |acc, (n, &x)| {
+            let angle = -T::two_pi() * T::from_usize((k*n) % len) / T::from_usize(len);
+            acc + x * Complex::from_polar(T::one(), angle)
+        })
+    }).collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FftError {
+    /// The input's length isn't a power of two, so the radix 2 FFT
+    /// can't split it in half at every stage. This holds the length
+    /// that was rejected.
+    NotPowerOfTwo(usize),
+    /// The input has no samples, or a two dimensional input has no
+    /// rows or no columns.
+    Empty,
+    /// The rows of a two dimensional input have different lengths.
+    RaggedRows
+}
+
+/// The fast Fourier transform.
+///
+/// This is a radix 2 FFT, so it gives the same result as `dft` in
+/// O(n log n) time, but only for inputs whose length is a power of
+/// two. As with `dft`, no normalization is applied in the forward
+/// direction.
+///
+/// # Errors
+///
+/// The input must have a length that is a power of two, and can't be
+/// empty.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::{fft, FftError};
+///
+/// let input = vec![Complex::new(1.0 as f32, 0.0); 6];
+/// assert_eq!(fft(&input), Err(FftError::NotPowerOfTwo(6)));
+///
+/// let input = vec![Complex::new(1.0 as f32, 0.0); 8];
+/// let spectrum = fft(&input).unwrap();
+/// assert_eq!(spectrum[0], Complex::new(8.0, 0.0));
+/// ```
+pub fn fft<T>(samples: &[Complex<T>]) -> Result<Vec<Complex<T>>, FftError> where T: Float {
+    let len = samples.len();
+    if len == 0 {
+        return Err(FftError::Empty);
+    }
+    if !len.is_power_of_two() {
+        return Err(FftError::NotPowerOfTwo(len));
+    }
+
+    let bits = len.trailing_zeros();
+    let mut result: Vec<Complex<T>> = (0..len)
+        .map(|i| if bits == 0 { samples[i] } else { samples[i.reverse_bits() >> (usize::BITS - bits)] })
+        .collect();
+
+    let mut size = 2;
+    while size <= len {
+        let half = size / 2;
+        let twiddles: Vec<Complex<T>> = (0..half)
+            .map(|k| Complex::from_polar(T::one(), -T::two_pi()*T::from_usize(k)/T::from_usize(size)))
+            .collect();
+        for start in (0..len).step_by(size) {
+            for (k, &twiddle) in twiddles.iter().enumerate() {
+                let even = result[start + k];
+                let odd = result[start + k + half] * twiddle;
+                result[start + k] = even + odd;
+                result[start + k + half] = even - odd;
+            }
+        }
+        size *= 2;
+    }
+    Ok(result)
+}
+
+/// The inverse fast Fourier transform.
+///
+/// The result is scaled by 1/N, so that `ifft(fft(x))` gives back
+/// `x`.
+///
+/// # Errors
+///
+/// The input must have a length that is a power of two, and can't be
+/// empty.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::{fft, ifft};
+///
+/// let input = vec![Complex::new(1.0 as f64, 2.0), Complex::new(-1.0, 0.5)];
+/// let round_trip = ifft(&fft(&input).unwrap()).unwrap();
+/// assert!((round_trip[0] - input[0]).magnitude() < 1e-12);
+/// assert!((round_trip[1] - input[1]).magnitude() < 1e-12);
+/// ```
+pub fn ifft<T>(spectrum: &[Complex<T>]) -> Result<Vec<Complex<T>>, FftError> where T: Float {
+    let conjugated: Vec<Complex<T>> = spectrum.iter().map(|x| x.conjugate()).collect();
+    let scale = T::from_usize(spectrum.len());
+    Ok(fft(&conjugated)?.into_iter()
+        .map(|x| {
+            let x = x.conjugate();
+            Complex::new(x.real/scale, x.imag/scale)
+        })
+        .collect())
+}
+
+/// The fast Fourier transform of a real signal, keeping only the
+/// non-negative frequency bins.
+///
+/// The spectrum of a real signal is Hermitian symmetric, so the
+/// negative frequency bins are just the conjugates of the positive
+/// ones. For an input of length N this gives bins 0 to N/2
+/// inclusive, which is N/2 + 1 bins.
+///
+/// # Errors
+///
+/// The input must have a length that is a power of two, and can't be
+/// empty.
+///
+/// ```
+/// use worthe_signals::dsp::rfft;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let spectrum = rfft(&[1.0 as f64, 0.0, -1.0, 0.0]).unwrap();
+/// assert_eq!(spectrum.len(), 3);
+/// assert!(spectrum[1].real.approx_eq(2.0));
+/// ```
+pub fn rfft<T>(samples: &[T]) -> Result<Vec<Complex<T>>, FftError> where T: Float {
+    let complex: Vec<Complex<T>> = samples.iter().map(|&x| Complex::new(x, T::zero())).collect();
+    let mut spectrum = fft(&complex)?;
+    spectrum.truncate(samples.len()/2 + 1);
+    Ok(spectrum)
+}
+
+/// The inverse of `rfft`, giving back a real signal of length
+/// `full_len` from its non-negative frequency bins.
+///
+/// `full_len` must be a power of two, and `half_spectrum` must have
+/// exactly `full_len/2 + 1` bins, so an 8 sample signal has 5 bins.
+/// The negative frequency bins are filled in as the conjugates of the
+/// positive ones, and any imaginary part in the DC bin (or the Nyquist
+/// bin, when there is more than one sample) is ignored, since it can't
+/// come from a real signal.
+///
+/// # Errors
+///
+/// `full_len` must be a power of two, and can't be 0.
+///
+/// # Panics
+///
+/// Panics if `half_spectrum` doesn't have `full_len/2 + 1` bins.
+///
+/// ```
+/// use worthe_signals::dsp::{rfft, irfft};
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let samples = vec![0.5 as f64, -1.0, 2.0, 0.25];
+/// let round_trip = irfft(&rfft(&samples).unwrap(), samples.len()).unwrap();
+/// for (&a, &b) in round_trip.iter().zip(samples.iter()) {
+///     assert!(a.approx_eq(b));
+/// }
+/// ```
+pub fn irfft<T>(half_spectrum: &[Complex<T>], full_len: usize) -> Result<Vec<T>, FftError> where T: Float {
+    assert_eq!(half_spectrum.len(), full_len/2 + 1,
+               "A signal of length {} needs {} bins", full_len, full_len/2 + 1);
+    let spectrum: Vec<Complex<T>> = (0..full_len)
+        .map(|k| if k < half_spectrum.len() { half_spectrum[k] } else { half_spectrum[full_len - k].conjugate() })
+        .collect();
+    Ok(ifft(&spectrum)?.into_iter().map(|x| x.real).collect())
+}
+
+/// The analytic signal of a real signal, which has the signal as its
+/// real part and the signal's Hilbert transform as its imaginary
+/// part.
+///
+/// The analytic signal has the same positive frequencies as the
+/// signal (doubled, to make up for the loss), and none of the
+/// negative frequencies. That makes it a rotating phasor, whose
+/// magnitude is the signal's envelope and whose angle is its
+/// instantaneous phase.
+///
+/// It is calculated by transforming the whole signal, so the signal
+/// is treated as though it repeats.
+///
+/// ```
+/// use worthe_signals::dsp::analytic_signal;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// // the Hilbert transform of a cosine is a sine
+/// let cosine = Sinusoid::new(1.0 as f64, 4.0, 0.0).sample_range(0.0, 1.0, 64.0);
+/// let analytic = analytic_signal(&cosine);
+/// for (n, x) in analytic.iter().enumerate() {
+///     let t = n as f64 / 64.0;
+///     assert!((x.imag - (2.0*std::f64::consts::PI*4.0*t).sin()).abs() < 1e-9);
+/// }
+/// ```
+pub fn analytic_signal<T>(samples: &[T]) -> Vec<Complex<T>> where T: Float {
+    let len = samples.len();
+    if len == 0 {
+        return Vec::new();
+    }
+    let complex: Vec<Complex<T>> = samples.iter().map(|&x| Complex::new(x, T::zero())).collect();
+    let spectrum = forward_any_length(&complex);
+
+    let two = T::from_f64(2.0);
+    let one_sided: Vec<Complex<T>> = spectrum.into_iter().enumerate()
+        .map(|(k, x)| {
+            if k == 0 || 2*k == len {
+                x
+            } else if 2*k < len {
+                Complex::new(x.real*two, x.imag*two)
+            } else {
+                Complex::new(T::zero(), T::zero())
+            }
+        })
+        .collect();
+
+    inverse_any_length(&one_sided)
+}
+
+/// Transforms any length of input, using `fft` if the length is a
+/// power of two, and falling back to the O(n²) `dft` otherwise. Prefer
+/// power of two lengths wherever the caller gets to choose, since the
+/// fallback is much slower for long inputs.
+pub(crate) fn forward_any_length<T>(samples: &[Complex<T>]) -> Vec<Complex<T>> where T: Float {
+    if samples.len().is_power_of_two() {
+        fft(samples).expect("length is a power of two")
+    } else {
+        dft(samples)
+    }
+}
+
+/// The inverse of `forward_any_length`, scaled by 1/N, using `ifft`
+/// or a conjugated `dft` in the same way.
+fn inverse_any_length<T>(spectrum: &[Complex<T>]) -> Vec<Complex<T>> where T: Float {
+    if spectrum.len().is_power_of_two() {
+        ifft(spectrum).expect("length is a power of two")
+    } else {
+        let conjugated: Vec<Complex<T>> = spectrum.iter().map(|x| x.conjugate()).collect();
+        let scale = T::from_usize(spectrum.len());
+        dft(&conjugated).into_iter()
+            .map(|x| Complex::new(x.real/scale, -x.imag/scale))
+            .collect()
+    }
+}
+
+/// Multiplies a frame of samples by a window, and takes its Fourier
+/// transform.
+///
+/// This is the analysis half of an analysis-modify-resynthesis
+/// chain, with `windowed_ifft` as the synthesis half. All N bins are
+/// returned, so the spectrum can be edited and inverted directly.
+/// An empty frame gives an empty spectrum.
+///
+/// # Panics
+///
+/// Panics if the window isn't the same length as the samples.
+///
+/// ```
+/// use worthe_signals::dsp::{windowed_fft, hann_window};
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let spectrum = windowed_fft(&[1.0 as f64; 8], &hann_window(8));
+/// assert!(spectrum[0].real.approx_eq(4.0));
+/// ```
+pub fn windowed_fft<T>(samples: &[T], window: &[T]) -> Vec<Complex<T>> where T: Float {
+    assert_eq!(samples.len(), window.len(), "The window must be the same length as the samples");
+    let windowed: Vec<Complex<T>> = samples.iter().zip(window.iter())
+        .map(|(&x, &w)| Complex::new(x*w, T::zero()))
+        .collect();
+    forward_any_length(&windowed)
+}
+
+/// The inverse of `windowed_fft`, taking the inverse Fourier
+/// transform and dividing the window back out.
+///
+/// An unmodified spectrum gives back the original samples, wherever
+/// the window isn't zero. To keep edits to the spectrum from being
+/// amplified without bound near the window's zeros, the window is
+/// floored at 1/1000 (-60 dB) of its peak before dividing; samples
+/// where the window is smaller than that come back attenuated. A
+/// window that is all zeros, or too small for that floor to be
+/// anything but zero, kept none of the signal, so the result is all
+/// zeros rather than NaN. Any imaginary part left by a spectrum that
+/// isn't Hermitian symmetric is discarded.
+///
+/// # Panics
+///
+/// Panics if the window isn't the same length as the spectrum.
+///
+/// ```
+/// use worthe_signals::dsp::{windowed_fft, windowed_ifft, hann_window};
+///
+/// let samples = [0.5 as f64, -1.0, 2.0, 0.25, 1.0, -0.75];
+/// let window = hann_window(6);
+/// let round_trip = windowed_ifft(&windowed_fft(&samples, &window), &window);
+/// for n in 1..6 {
+///     assert!((round_trip[n] - samples[n]).abs() < 1e-9);
+/// }
+/// ```
+pub fn windowed_ifft<T>(spectrum: &[Complex<T>], window: &[T]) -> Vec<T> where T: Float {
+    assert_eq!(spectrum.len(), window.len(), "The window must be the same length as the spectrum");
+    let peak = window.iter().fold(T::zero(), |max, &w| if w.abs() > max { w.abs() } else { max });
+    let floor = peak / T::from_f64(1000.0);
+    if floor == T::zero() {
+        return vec![T::zero(); spectrum.len()];
+    }
+    inverse_any_length(spectrum).into_iter().zip(window.iter())
+        .map(|(x, &w)| {
+            if w.abs() >= floor {
+                x.real / w
+            } else if w < T::zero() {
+                -x.real / floor
+            } else {
+                x.real / floor
+            }
+        })
+        .collect()
+}
+
+/// The real signal whose spectrum has the given magnitudes and zero
+/// phase.
+///
+/// The magnitudes are one sided, running from 0 Hz to Nyquist
+/// inclusive like the output of `rfft`, so M bins give a signal of
+/// 2(M-1) samples. The negative frequencies mirror the positive ones,
+/// so the signal is real, and the zero phase makes it even:
+/// `x[n] = x[N-n]`, with its peak at sample 0.
+///
+/// ```
+/// use worthe_signals::dsp::zero_phase_reconstruct;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// // a flat spectrum is an impulse
+/// let signal = zero_phase_reconstruct(&[1.0 as f64; 5]);
+/// assert_eq!(signal.len(), 8);
+/// assert!(signal[0].approx_eq(1.0));
+/// assert!(signal[1..].iter().all(|x| x.approx_eq(0.0)));
+/// ```
+pub fn zero_phase_reconstruct<T>(magnitude: &[T]) -> Vec<T> where T: Float {
+    let bins = magnitude.len();
+    let len = if bins > 1 { 2*(bins - 1) } else { bins };
+    let spectrum: Vec<Complex<T>> = (0..len)
+        .map(|k| Complex::new(magnitude[if k < bins { k } else { len - k }], T::zero()))
+        .collect();
+    inverse_any_length(&spectrum).into_iter().map(|x| x.real).collect()
+}
+
+/// The two dimensional fast Fourier transform, of a grid given as a
+/// list of rows.
+///
+/// This applies `fft` along each row, and then along each column of
+/// the result. As with `fft`, no normalization is applied.
+///
+/// # Errors
+///
+/// All of the rows must be the same length, and both the number of
+/// rows and the row length must be powers of two.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::fft2;
+///
+/// let grid = vec![vec![Complex::new(1.0 as f64, 0.0); 4]; 2];
+/// let spectrum = fft2(&grid).unwrap();
+/// assert_eq!(spectrum[0][0], Complex::new(8.0, 0.0));
+/// assert_eq!(spectrum[1][3], Complex::new(0.0, 0.0));
+/// ```
+pub fn fft2<T>(data: &[Vec<Complex<T>>]) -> Result<Vec<Vec<Complex<T>>>, FftError> where T: Float {
+    transform_2d(data, fft)
+}
+
+/// The inverse of `fft2`, scaled by 1/(rows·columns) so that
+/// `ifft2(fft2(x))` gives back `x`.
+///
+/// # Errors
+///
+/// All of the rows must be the same length, and both the number of
+/// rows and the row length must be powers of two.
+pub fn ifft2<T>(spectrum: &[Vec<Complex<T>>]) -> Result<Vec<Vec<Complex<T>>>, FftError> where T: Float {
+    transform_2d(spectrum, ifft)
+}
+
+fn transform_2d<T, F>(data: &[Vec<Complex<T>>], transform: F) -> Result<Vec<Vec<Complex<T>>>, FftError>
+    where T: Float, F: Fn(&[Complex<T>]) -> Result<Vec<Complex<T>>, FftError>
+{
+    let columns = data.first().map_or(0, |row| row.len());
+    if data.iter().any(|row| row.len() != columns) {
+        return Err(FftError::RaggedRows);
+    }
+
+    let rows = data.iter()
+        .map(|row| transform(row))
+        .collect::<Result<Vec<_>, _>>()?;
+    let transformed_columns = (0..columns)
+        .map(|c| {
+            let column: Vec<Complex<T>> = rows.iter().map(|row| row[c]).collect();
+            transform(&column)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if transformed_columns.is_empty() {
+        return Err(FftError::Empty);
+    }
+
+    Ok((0..rows.len())
+        .map(|r| transformed_columns.iter().map(|column| column[r]).collect())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+    use ::dsp::noise;
+
+    fn random_complex(len: usize, seed: u64) -> Vec<Complex<f64>> {
+        let real: Vec<f64> = noise::white(len, 1.0, seed);
+        let imag: Vec<f64> = noise::white(len, 1.0, seed + 1);
+        real.into_iter().zip(imag).map(|(r, i)| Complex::new(r, i)).collect()
+    }
+
+    #[test]
+    fn fft_rejects_bad_lengths() {
+        let empty: Vec<Complex<f64>> = Vec::new();
+        assert_eq!(fft(&empty), Err(FftError::Empty));
+        assert_eq!(ifft(&empty), Err(FftError::Empty));
+        assert_eq!(fft(&random_complex(6, 1)), Err(FftError::NotPowerOfTwo(6)));
+        assert_eq!(ifft(&random_complex(12, 1)), Err(FftError::NotPowerOfTwo(12)));
+    }
+
+    #[test]
+    fn fft_matches_dft() {
+        for &len in &[1, 2, 8, 64] {
+            let input = random_complex(len, len as u64);
+            let fast = fft(&input).unwrap();
+            let slow = dft(&input);
+            for (k, (a, b)) in fast.iter().zip(slow.iter()).enumerate() {
+                assert!((*a - *b).magnitude() < 1e-9, "Bin {} of {} was {:?}, expected {:?}", k, len, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn ifft_inverts_fft() {
+        let input = random_complex(32, 5);
+        let round_trip = ifft(&fft(&input).unwrap()).unwrap();
+        for (a, b) in round_trip.iter().zip(input.iter()) {
+            assert!((*a - *b).magnitude() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn irfft_inverts_rfft() {
+        let samples: Vec<f64> = noise::white(64, 1.0, 3);
+        let spectrum = rfft(&samples).unwrap();
+        assert_eq!(spectrum.len(), 33);
+        let complex: Vec<Complex<f64>> = samples.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        let full = fft(&complex).unwrap();
+        for (a, b) in spectrum.iter().zip(full.iter()) {
+            assert!((*a - *b).magnitude() < 1e-12);
+        }
+
+        let round_trip = irfft(&spectrum, samples.len()).unwrap();
+        assert_eq!(round_trip.len(), samples.len());
+        for (&a, &b) in round_trip.iter().zip(samples.iter()) {
+            assert!(a.approx_eq(b));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn irfft_needs_matching_bin_count() {
+        let spectrum = vec![Complex::new(1.0_f64, 0.0); 4];
+        let _ = irfft(&spectrum, 8);
+    }
+
+    #[test]
+    fn analytic_signal_of_any_length() {
+        use ::sinusoid::Sinusoid;
+        // 3 Hz for 2 seconds at 30 samples per second, which isn't a
+        // power of two long
+        let tone: Sinusoid<f64> = Sinusoid::new(0.7, 3.0, 0.4);
+        let samples = tone.sample_range(0.0, 2.0, 30.0);
+        assert_eq!(samples.len(), 60);
+        let analytic = analytic_signal(&samples);
+        for (n, (x, &real)) in analytic.iter().zip(samples.iter()).enumerate() {
+            assert!((x.real - real).abs() < 1e-9);
+            assert!((x.magnitude() - 0.7).abs() < 1e-9, "Envelope at {} was {}", n, x.magnitude());
+        }
+    }
+
+    #[test]
+    fn windowed_ifft_inverts_windowed_fft() {
+        use ::dsp::hann_window;
+        for &len in &[256, 100] {
+            let samples: Vec<f64> = noise::white(len, 1.0, 8);
+            let window: Vec<f64> = hann_window(len);
+            let round_trip = windowed_ifft(&windowed_fft(&samples, &window), &window);
+            assert_eq!(round_trip.len(), len);
+            for n in (0..len).filter(|&n| window[n] > 1e-3) {
+                assert!((round_trip[n] - samples[n]).abs() < 1e-9,
+                        "Sample {} of {} was {}, expected {}", n, len, round_trip[n], samples[n]);
+            }
+        }
+    }
+
+    #[test]
+    fn windowed_ifft_of_zero_window_is_silent() {
+        let spectrum = random_complex(16, 3);
+        assert_eq!(windowed_ifft(&spectrum, &[0.0; 16]), vec![0.0; 16]);
+        assert!(windowed_ifft::<f64>(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn zero_phase_reconstruction_is_even_with_matching_magnitude() {
+        for &bins in &[65, 50] {
+            let magnitude: Vec<f64> = noise::white(bins, 1.0, 12).into_iter().map(|x: f64| x.abs() + 0.1).collect();
+            let signal = zero_phase_reconstruct(&magnitude);
+            let len = 2*(bins - 1);
+            assert_eq!(signal.len(), len);
+            for n in 1..len {
+                assert!(signal[n].approx_eq(signal[len - n]), "Sample {} of {} wasn't mirrored", n, len);
+            }
+
+            let complex: Vec<Complex<f64>> = signal.iter().map(|&x| Complex::new(x, 0.0)).collect();
+            let spectrum = dft(&complex);
+            for (k, &m) in magnitude.iter().enumerate() {
+                assert!((spectrum[k].magnitude() - m).abs() < 1e-9, "Bin {} was {:?}, expected {}", k, spectrum[k], m);
+            }
+        }
+    }
+
+    #[test]
+    fn ifft2_inverts_fft2() {
+        let grid: Vec<Vec<Complex<f64>>> = (0..4).map(|r| random_complex(4, 10 + r)).collect();
+        let round_trip = ifft2(&fft2(&grid).unwrap()).unwrap();
+        for (row, expected) in round_trip.iter().zip(grid.iter()) {
+            for (a, b) in row.iter().zip(expected.iter()) {
+                assert!((*a - *b).magnitude() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn fft2_of_impulse_is_flat() {
+        let mut grid = vec![vec![Complex::new(0.0_f64, 0.0); 4]; 4];
+        grid[1][2] = Complex::new(1.0, 0.0);
+        for row in fft2(&grid).unwrap() {
+            for bin in row {
+                assert!(bin.magnitude().approx_eq(1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn fft2_rejects_bad_shapes() {
+        let empty: Vec<Vec<Complex<f64>>> = Vec::new();
+        assert_eq!(fft2(&empty), Err(FftError::Empty));
+        assert_eq!(fft2(&[Vec::<Complex<f64>>::new()]), Err(FftError::Empty));
+        assert_eq!(fft2(&[random_complex(4, 1), random_complex(2, 2)]), Err(FftError::RaggedRows));
+        let three_rows: Vec<Vec<Complex<f64>>> = (0..3).map(|r| random_complex(4, r)).collect();
+        assert_eq!(fft2(&three_rows), Err(FftError::NotPowerOfTwo(3)));
+    }
+}
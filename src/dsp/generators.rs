@@ -0,0 +1,381 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+use ::signal::Signal;
+use ::sinusoid::Sinusoid;
+
+/// A linear frequency sweep, with its frequency rising by twice
+/// `sweep_rate` Hz every second.
+struct Chirp<T> {
+    f0: T,
+    sweep_rate: T
+}
+
+impl<T> Signal<T> for Chirp<T> where T: Float {
+    fn sample(&self, t: T) -> T {
+        (T::two_pi() * (self.f0*t + self.sweep_rate*t*t)).cos()
+    }
+}
+
+/// Generates a linear frequency sweep, sampled at `rate` samples per
+/// second.
+///
+/// The instantaneous frequency starts at `f0` Hz and rises (or falls)
+/// linearly to `f1` Hz over `duration` seconds. The phase is the
+/// integral of the instantaneous frequency, `2π(f0·t + (f1-f0)·t²/2d)`,
+/// so the sweep has no discontinuities. Like `Sinusoid`, the sweep is
+/// a cosine, so it starts at 1.
+///
+/// Samples are taken at the same times as `sample_range`, from t=0 up
+/// to, but not including, `duration`.
+///
+/// ```
+/// use worthe_signals::dsp::chirp;
+///
+/// let sweep = chirp(20.0 as f32, 200.0, 0.5, 1000.0);
+/// assert_eq!(sweep.len(), 500);
+/// assert!((sweep[0] - 1.0).abs() < 1e-6);
+/// ```
+pub fn chirp<T>(f0: T, f1: T, duration: T, rate: T) -> Vec<T> where T: Float {
+    let two = T::from_f64(2.0);
+    let sweep = Chirp { f0, sweep_rate: (f1 - f0) / (two*duration) };
+    sweep.sample_range(T::zero(), duration, rate)
+}
+
+/// A weighted sum of signals, which is itself a signal.
+struct Mix<'a, 'b, T> where T: 'a + 'b {
+    sources: &'a [(&'b dyn Signal<T>, T)]
+}
+
+impl<'a, 'b, T> Signal<T> for Mix<'a, 'b, T> where T: Float {
+    fn sample(&self, t: T) -> T {
+        self.sources.iter()
+            .fold(T::zero(), |acc, &(source, gain)| acc + gain*source.sample(t))
+    }
+}
+
+/// Samples each of the sources over a range, scales them by their
+/// gain, and adds them together.
+///
+/// The samples are taken at the same times as `sample_range`. With no
+/// sources, the result is silence over the range.
+///
+/// ```
+/// use worthe_signals::dsp::mix;
+/// use worthe_signals::sinusoid::Sinusoid;
+/// use worthe_signals::waves::SquareWave;
+///
+/// let tone = Sinusoid::new(1.0 as f32, 2.0, 0.0);
+/// let square = SquareWave::new(1.0 as f32, 2.0, 0.0);
+/// let mixed = mix(&[(&tone, 0.5), (&square, 0.25)], 0.0, 1.0, 8.0);
+/// assert_eq!(mixed.len(), 8);
+/// assert!((mixed[0] - 0.75).abs() < 1e-6);
+/// ```
+pub fn mix<T>(sources: &[(&dyn Signal<T>, T)], start: T, end: T, sample_rate: T) -> Vec<T> where T: Float {
+    Mix { sources }.sample_range(start, end, sample_rate)
+}
+
+/// A carrier frequency modulated by a sinusoidal message.
+struct FmTone<'a, T> where T: 'a {
+    carrier_freq: T,
+    message: &'a Sinusoid<T>,
+    modulation_index: T
+}
+
+impl<'a, T> FmTone<'a, T> where T: Float {
+    /// The integral of the message from 0 to t.
+    fn message_integral(&self, t: T) -> T {
+        let message = self.message;
+        if message.frequency == T::zero() {
+            message.amplitude*message.phase.cos()*t
+        } else {
+            let omega = message.radial_frequency();
+            message.amplitude*((omega*t + message.phase).sin() - message.phase.sin())/omega
+        }
+    }
+}
+
+impl<'a, T> Signal<T> for FmTone<'a, T> where T: Float {
+    fn sample(&self, t: T) -> T {
+        (T::two_pi()*(self.carrier_freq*t + self.modulation_index*self.message_integral(t))).cos()
+    }
+}
+
+/// Frequency modulates a carrier with a sinusoidal message.
+///
+/// The instantaneous frequency is `carrier_freq + modulation_index·message(t)`,
+/// so `modulation_index` is the frequency deviation in Hz for each
+/// unit of the message. The phase is the exact integral of that
+/// frequency from t=0, so the output is phase continuous. Its energy
+/// spreads into sidebands spaced at the message frequency around the
+/// carrier, with amplitudes given by Bessel functions of
+/// `modulation_index·message.amplitude/message.frequency`.
+///
+/// Samples are taken at the same times as `sample_range`.
+///
+/// ```
+/// use worthe_signals::dsp::fm_synthesize;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// let message = Sinusoid::new(1.0 as f32, 5.0, 0.0);
+/// let fm = fm_synthesize(100.0, &message, 20.0, 0.0, 1.0, 1000.0);
+/// assert_eq!(fm.len(), 1000);
+/// assert!((fm[0] - 1.0).abs() < 1e-6);
+/// ```
+pub fn fm_synthesize<T>(carrier_freq: T, message: &Sinusoid<T>, modulation_index: T, start: T, end: T, sample_rate: T) -> Vec<T> where T: Float {
+    FmTone { carrier_freq, message, modulation_index }.sample_range(start, end, sample_rate)
+}
+
+/// Samples `first` for `first_duration` seconds and then `second` for
+/// `second_duration` seconds, without a jump in phase between them.
+///
+/// The second tone starts from the phase the first one had reached
+/// when it stopped, so its own phase is ignored. With the phase
+/// continuous, a change in frequency doesn't click, although a
+/// change in amplitude still steps. Each tone is sampled the same way
+/// `sample_range` would, starting again from t=0.
+///
+/// ```
+/// use worthe_signals::dsp::concat_phase_continuous;
+/// use worthe_signals::sinusoid::Sinusoid;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let low = Sinusoid::new(1.0 as f64, 1.0, 0.0);
+/// let high = Sinusoid::new(1.0 as f64, 2.0, 0.0);
+/// let samples = concat_phase_continuous(&low, 0.5, &high, 0.5, 8.0);
+/// // the first tone stops half way through its period, so the second
+/// // picks up from the trough
+/// assert_eq!(samples.len(), 8);
+/// assert!(samples[4].approx_eq(-1.0));
+/// ```
+pub fn concat_phase_continuous<T>(first: &Sinusoid<T>, first_duration: T, second: &Sinusoid<T>, second_duration: T, sample_rate: T) -> Vec<T> where T: Float {
+    let mut result = first.sample_range(T::zero(), first_duration, sample_rate);
+    let junction = T::from_usize(result.len()) / sample_rate;
+    let continued = Sinusoid::new(second.amplitude, second.frequency, first.instantaneous_phase(junction));
+    result.extend(continued.sample_range(T::zero(), second_duration, sample_rate));
+    result
+}
+
+/// A discrete impulse: all zeros, except for `amplitude` at
+/// `position`. If the position is past the end, it's all zeros.
+///
+/// ```
+/// use worthe_signals::dsp::impulse;
+///
+/// assert_eq!(impulse(5, 2, 1.0 as f32), vec![0.0, 0.0, 1.0, 0.0, 0.0]);
+/// ```
+pub fn impulse<T>(len: usize, position: usize, amplitude: T) -> Vec<T> where T: Float {
+    (0..len).map(|n| if n == position { amplitude } else { T::zero() }).collect()
+}
+
+/// A discrete step: zeros before `position`, and `amplitude` from
+/// `position` onwards. If the position is past the end, it's all
+/// zeros.
+///
+/// ```
+/// use worthe_signals::dsp::step;
+///
+/// assert_eq!(step(5, 1, 2.0 as f32), vec![0.0, 2.0, 2.0, 2.0, 2.0]);
+/// ```
+pub fn step<T>(len: usize, position: usize, amplitude: T) -> Vec<T> where T: Float {
+    (0..len).map(|n| if n >= position { amplitude } else { T::zero() }).collect()
+}
+
+/// A straight line from `start` to `end` inclusive, over `len`
+/// samples. A single sample is just `start`.
+///
+/// ```
+/// use worthe_signals::dsp::ramp;
+///
+/// assert_eq!(ramp(1.0 as f32, 2.0, 5), vec![1.0, 1.25, 1.5, 1.75, 2.0]);
+/// ```
+pub fn ramp<T>(start: T, end: T, len: usize) -> Vec<T> where T: Float {
+    if len == 1 {
+        return vec![start];
+    }
+    let step = (end - start) / T::from_usize(len.saturating_sub(1).max(1));
+    (0..len).map(|n| start + step*T::from_usize(n)).collect()
+}
+
+/// An exponential decay, `initial·e^(-t/τ)`, sampled from t=0.
+///
+/// After each time constant `τ` (in seconds), the signal has fallen
+/// to 1/e of what it was.
+///
+/// ```
+/// use worthe_signals::dsp::exp_decay;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let decay = exp_decay(2.0 as f64, 0.5, 4, 2.0);
+/// assert!(decay[1].approx_eq(2.0/std::f64::consts::E));
+/// ```
+pub fn exp_decay<T>(initial: T, time_constant: T, len: usize, sample_rate: T) -> Vec<T> where T: Float {
+    let samples_per_constant = time_constant*sample_rate;
+    (0..len).map(|n| initial*(-T::from_usize(n)/samples_per_constant).exp()).collect()
+}
+
+/// Evaluates the complex exponential `e^(pole·t)` at time `t`.
+///
+/// This is the time domain response of a single pole of a transfer
+/// function. The imaginary part of the pole sets how fast the result
+/// rotates, in radians per second, and the real part sets how fast it
+/// grows (if positive) or decays (if negative).
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::eval_exponential;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let decay = eval_exponential(Complex::new(-2.0 as f64, 0.0), 0.5);
+/// assert!(decay.real.approx_eq((-1.0 as f64).exp()));
+/// assert_eq!(decay.imag, 0.0);
+/// ```
+pub fn eval_exponential<T>(pole: Complex<T>, t: T) -> Complex<T> where T: Float {
+    Complex::new(pole.real*t, pole.imag*t).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+
+    /// Times, in seconds, where the signal crosses zero. Linear
+    /// interpolation is used to place the crossing between samples.
+    fn zero_crossings(samples: &[f64], rate: f64) -> Vec<f64> {
+        samples.windows(2).enumerate()
+            .filter(|&(_, pair)| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .map(|(i, pair)| (i as f64 + pair[0] / (pair[0] - pair[1])) / rate)
+            .collect()
+    }
+
+    #[test]
+    fn mix_is_weighted_sum() {
+        use ::sinusoid::Sinusoid;
+
+        let a: Sinusoid<f64> = Sinusoid::new(1.0, 3.0, 0.2);
+        let b: Sinusoid<f64> = Sinusoid::new(2.0, 7.0, -1.0);
+        let mixed = mix(&[(&a, 1.0), (&b, 0.5)], 0.0, 1.0, 100.0);
+        assert_eq!(mixed.len(), 100);
+        for &i in &[0, 13, 50, 99] {
+            let t = i as f64 / 100.0;
+            let expected = a.sample(t) + 0.5*b.sample(t);
+            assert!(mixed[i].approx_eq(expected), "Sample {} was {}, expected {}", i, mixed[i], expected);
+        }
+    }
+
+    #[test]
+    fn mix_of_nothing_is_silence() {
+        assert_eq!(mix::<f64>(&[], 0.0, 1.0, 4.0), vec![0.0; 4]);
+    }
+
+    #[test]
+    fn fm_sidebands_follow_bessel_functions() {
+        use ::complex::Complex;
+        use ::dsp::dft;
+
+        let message: Sinusoid<f64> = Sinusoid::new(1.0, 10.0, 0.0);
+        // a deviation of 10 Hz with a 10 Hz message is a modulation
+        // index of 1.
+        let fm = fm_synthesize(100.0, &message, 10.0, 0.0, 1.0, 1000.0);
+        let input: Vec<Complex<f64>> = fm.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        let spectrum: Vec<f64> = dft(&input).iter().map(|x| x.magnitude() / 500.0).collect();
+
+        // J0(1), J1(1), J2(1), J3(1)
+        let bessel = [0.765_197_686_6, 0.440_050_585_7, 0.114_903_484_9, 0.019_563_353_98];
+        for (n, j) in bessel.iter().enumerate() {
+            let upper = spectrum[100 + 10*n];
+            let lower = spectrum[100 - 10*n];
+            assert!((upper - j).abs() < 1e-3, "Upper sideband {} was {}, expected {}", n, upper, j);
+            assert!((lower - j).abs() < 1e-3, "Lower sideband {} was {}, expected {}", n, lower, j);
+        }
+        for (k, x) in spectrum.iter().enumerate().take(500) {
+            if k % 10 != 0 {
+                assert!(*x < 1e-6, "Bin {} between sidebands was {}", k, x);
+            }
+        }
+    }
+
+    #[test]
+    fn chirp_length() {
+        assert_eq!(chirp(100.0_f64, 1000.0, 1.0, 48000.0).len(), 48000);
+        assert_eq!(chirp(100.0_f64, 1000.0, 0.25, 8000.0).len(), 2000);
+    }
+
+    #[test]
+    fn chirp_sweeps_from_start_to_end_frequency() {
+        let rate = 48000.0;
+        let sweep = chirp(100.0, 1000.0, 1.0, rate);
+        let crossings = zero_crossings(&sweep, rate);
+
+        // consecutive zero crossings are half a period apart
+        let start_freq = 1.0 / (2.0*(crossings[1] - crossings[0]));
+        let n = crossings.len();
+        let end_freq = 1.0 / (2.0*(crossings[n-1] - crossings[n-2]));
+
+        assert!((start_freq - 100.0).abs() < 5.0, "Start frequency was {}", start_freq);
+        assert!((end_freq - 1000.0).abs() < 10.0, "End frequency was {}", end_freq);
+    }
+
+    #[test]
+    fn phase_continuous_concatenation_has_no_click() {
+        let first: Sinusoid<f64> = Sinusoid::new(1.0, 440.0, 0.3);
+        let second: Sinusoid<f64> = Sinusoid::new(1.0, 660.0, 2.0);
+        let samples = concat_phase_continuous(&first, 0.0123, &second, 0.01, 48000.0);
+        let first_len = first.sample_range(0.0, 0.0123, 48000.0).len();
+        assert_eq!(samples.len(), first_len + 480);
+
+        // a sinusoid can't change by more than 2πfA per second
+        let max_step = 2.0 * std::f64::consts::PI * 660.0 / 48000.0;
+        for (n, pair) in samples.windows(2).enumerate() {
+            assert!((pair[1] - pair[0]).abs() <= max_step, "Jump of {} at {}", pair[1] - pair[0], n);
+        }
+
+        // without the phase adjustment, there is a click
+        let mut naive = first.sample_range(0.0, 0.0123, 48000.0);
+        naive.extend(second.sample_range(0.0, 0.01, 48000.0));
+        let jump = (naive[first_len] - naive[first_len - 1]).abs();
+        assert!(jump > max_step, "Jump was only {}", jump);
+    }
+
+    #[test]
+    fn ramp_endpoints() {
+        let up: Vec<f64> = ramp(-1.0, 3.0, 101);
+        assert_eq!(up.len(), 101);
+        assert_eq!(up[0], -1.0);
+        assert!(up[100].approx_eq(3.0));
+        assert!(up[50].approx_eq(1.0));
+        assert!(ramp(0.0_f64, 1.0, 0).is_empty());
+        assert_eq!(ramp(0.5_f64, 1.0, 1), vec![0.5]);
+    }
+
+    #[test]
+    fn exp_decay_falls_by_e_each_time_constant() {
+        let decay: Vec<f64> = exp_decay(0.8, 0.01, 1000, 48000.0);
+        assert_eq!(decay[0], 0.8);
+        assert!(decay[480].approx_eq(0.8/std::f64::consts::E));
+        assert!(decay[960].approx_eq(0.8/std::f64::consts::E.powi(2)));
+        assert!(decay.windows(2).all(|pair| pair[1] < pair[0]));
+    }
+
+    #[test]
+    fn imaginary_pole_oscillates_without_damping() {
+        let omega = 2.0 * std::f64::consts::PI * 5.0;
+        let pole = Complex::new(0.0, omega);
+        for n in 0..100 {
+            let t = n as f64 * 0.013;
+            let value = eval_exponential(pole, t);
+            assert!(value.magnitude().approx_eq(1.0));
+            assert!(value.real.approx_eq((omega*t).cos()));
+        }
+    }
+
+    #[test]
+    fn negative_real_pole_decays() {
+        let pole = Complex::new(-3.0_f64, 20.0);
+        let magnitudes: Vec<f64> = (0..50)
+            .map(|n| eval_exponential(pole, n as f64 * 0.1).magnitude())
+            .collect();
+        assert!(magnitudes[0].approx_eq(1.0));
+        assert!(magnitudes.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(magnitudes[10].approx_eq((-3.0_f64).exp()));
+    }
+}
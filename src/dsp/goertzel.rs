@@ -0,0 +1,125 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+
+/// Evaluates a single frequency component of a block of samples using
+/// the Goertzel algorithm.
+///
+/// The result is the same as the DFT term `Σ x[n]·e^(-jωn)` with
+/// `ω = 2π·target_freq/sample_rate`, but it only needs a single real
+/// multiplication per sample. The target frequency doesn't need to
+/// line up with a DFT bin.
+///
+/// ```
+/// use worthe_signals::dsp::goertzel;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// let tone = Sinusoid::new(1.0 as f64, 1000.0, 0.0);
+/// let samples = tone.sample_range(0.0, 0.1, 8000.0);
+/// let present = goertzel(&samples, 1000.0, 8000.0).magnitude();
+/// let absent = goertzel(&samples, 1500.0, 8000.0).magnitude();
+/// assert!((present - 400.0).abs() < 1e-6);
+/// assert!(absent < 1e-6);
+/// ```
+pub fn goertzel<T>(samples: &[T], target_freq: T, sample_rate: T) -> Complex<T> where T: Float {
+    let mut detector = GoertzelDetector::new(target_freq, sample_rate);
+    for &x in samples {
+        detector.push(x);
+    }
+
+    // The recurrence gives the component relative to the last sample,
+    // so it is rotated back to be relative to the first.
+    let omega = detector.omega;
+    let last = Complex::new(detector.s1, T::zero())
+        - Complex::from_polar(detector.s2, -omega);
+    if samples.is_empty() {
+        last
+    } else {
+        last * Complex::from_polar(T::one(), -omega*T::from_usize(samples.len() - 1))
+    }
+}
+
+/// A streaming version of the Goertzel algorithm, for detecting a tone
+/// in a live signal.
+///
+/// Samples are pushed in one at a time, and the magnitude of the
+/// target frequency over all of the samples since the last reset can
+/// be read at any point.
+///
+/// ```
+/// use worthe_signals::dsp::GoertzelDetector;
+///
+/// let mut detector = GoertzelDetector::new(1000.0 as f32, 8000.0);
+/// for x in &[1.0, 0.0, -1.0, 0.0, 1.0, 0.0, -1.0, 0.0] {
+///     detector.push(*x);
+/// }
+/// assert!(detector.magnitude() > 0.0);
+/// detector.reset();
+/// assert_eq!(detector.magnitude(), 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoertzelDetector<T> {
+    omega: T,
+    coeff: T,
+    s1: T,
+    s2: T
+}
+
+impl<T> GoertzelDetector<T> where T: Float {
+    pub fn new(target_freq: T, sample_rate: T) -> GoertzelDetector<T> {
+        let omega = T::two_pi() * target_freq / sample_rate;
+        GoertzelDetector {
+            omega,
+            coeff: T::from_f64(2.0) * omega.cos(),
+            s1: T::zero(),
+            s2: T::zero()
+        }
+    }
+
+    pub fn push(&mut self, sample: T) {
+        let s = sample + self.coeff*self.s1 - self.s2;
+        self.s2 = self.s1;
+        self.s1 = s;
+    }
+
+    /// The magnitude of the target frequency component of all of the
+    /// samples pushed since the detector was created or reset.
+    pub fn magnitude(&self) -> T {
+        (self.s1*self.s1 + self.s2*self.s2 - self.coeff*self.s1*self.s2).sqrt()
+    }
+
+    pub fn reset(&mut self) {
+        self.s1 = T::zero();
+        self.s2 = T::zero();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::dsp::dft;
+    use ::sinusoid::Sinusoid;
+
+    #[test]
+    fn block_goertzel_matches_dft() {
+        let tone: Sinusoid<f64> = Sinusoid::new(0.7, 3.0, 0.4);
+        let samples = tone.sample_range(0.0, 1.0, 32.0);
+        let input: Vec<Complex<f64>> = samples.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        let spectrum = dft(&input);
+        for (k, expected) in spectrum.iter().enumerate() {
+            let actual = goertzel(&samples, k as f64, 32.0);
+            assert!((actual - *expected).magnitude() < 1e-9, "Bin {} was {:?}, expected {:?}", k, actual, expected);
+        }
+    }
+
+    #[test]
+    fn streaming_matches_block() {
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 941.0, 0.0);
+        let samples = tone.sample_range(0.0, 0.025, 8000.0);
+        let mut detector = GoertzelDetector::new(941.0, 8000.0);
+        for &x in &samples {
+            detector.push(x);
+        }
+        let block = goertzel(&samples, 941.0, 8000.0).magnitude();
+        assert!((detector.magnitude() - block).abs() < 1e-9, "Streaming was {}, block was {}", detector.magnitude(), block);
+    }
+}
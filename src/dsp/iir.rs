@@ -0,0 +1,351 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+
+/// A second order IIR filter section.
+///
+/// The coefficients are normalized so that a0 is 1, giving the
+/// difference equation
+/// `y[n] = b0·x[n] + b1·x[n-1] + b2·x[n-2] - a1·y[n-1] - a2·y[n-2]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Biquad<T> {
+    pub b0: T,
+    pub b1: T,
+    pub b2: T,
+    pub a1: T,
+    pub a2: T,
+    x1: T,
+    x2: T,
+    y1: T,
+    y2: T
+}
+
+impl<T> Biquad<T> where T: Float {
+    pub fn new(b0: T, b1: T, b2: T, a1: T, a2: T) -> Biquad<T> {
+        Biquad {
+            b0,
+            b1,
+            b2,
+            a1,
+            a2,
+            x1: T::zero(),
+            x2: T::zero(),
+            y1: T::zero(),
+            y2: T::zero()
+        }
+    }
+
+    /// A second order low pass filter, using the design from Robert
+    /// Bristow-Johnson's Audio EQ Cookbook.
+    ///
+    /// A `q` of 1/√2 gives a Butterworth response, which is as flat as
+    /// possible in the pass band.
+    pub fn low_pass(cutoff: T, q: T, sample_rate: T) -> Biquad<T> {
+        let two = T::from_f64(2.0);
+        let omega = T::two_pi() * cutoff / sample_rate;
+        let cos = omega.cos();
+        let alpha = omega.sin() / (two*q);
+
+        let a0 = T::one() + alpha;
+        let b1 = (T::one() - cos) / a0;
+        Biquad::new(b1/two, b1, b1/two, -two*cos/a0, (T::one() - alpha)/a0)
+    }
+
+    /// Filters a single sample.
+    pub fn process(&mut self, x: T) -> T {
+        let y = self.b0*x + self.b1*self.x1 + self.b2*self.x2
+            - self.a1*self.y1 - self.a2*self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+
+    /// Clears the filter's memory of previous samples.
+    pub fn reset(&mut self) {
+        self.x1 = T::zero();
+        self.x2 = T::zero();
+        self.y1 = T::zero();
+        self.y2 = T::zero();
+    }
+
+    /// Evaluates the filter's frequency response, H(e^jω), at a
+    /// frequency given in Hz.
+    ///
+    /// ```
+    /// use worthe_signals::dsp::iir::Biquad;
+    ///
+    /// let filter = Biquad::low_pass(1000.0 as f64, 0.5f64.sqrt(), 48000.0);
+    /// let cutoff_gain = filter.response_at(1000.0, 48000.0).magnitude();
+    /// assert!((cutoff_gain - 0.5f64.sqrt()).abs() < 1e-9);
+    /// ```
+    pub fn response_at(&self, freq: T, sample_rate: T) -> Complex<T> {
+        let omega = T::two_pi() * freq / sample_rate;
+        let z1 = Complex::new(T::zero(), -omega).exp();
+        let z2 = z1.powi(2);
+        let coefficient = |c: T| Complex::new(c, T::zero());
+
+        let numerator = coefficient(self.b0) + coefficient(self.b1)*z1 + coefficient(self.b2)*z2;
+        let denominator = coefficient(T::one()) + coefficient(self.a1)*z1 + coefficient(self.a2)*z2;
+        numerator / denominator
+    }
+}
+
+/// A one pole DC blocking filter, `y[n] = x[n] - x[n-1] + R·y[n-1]`.
+///
+/// This removes any constant offset from a stream of samples, while
+/// barely affecting anything above a low cutoff frequency. The closer
+/// the pole `R` is to 1, the lower that cutoff is, but the longer the
+/// filter takes to settle. The default pole is 0.995.
+///
+/// ```
+/// use worthe_signals::dsp::iir::DcBlocker;
+///
+/// let mut blocker = DcBlocker::default();
+/// let mut y = 0.0 as f32;
+/// for _ in 0..5000 {
+///     y = blocker.process(1.0);
+/// }
+/// assert!(y.abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcBlocker<T> {
+    pub pole: T,
+    x1: T,
+    y1: T
+}
+
+impl<T> DcBlocker<T> where T: Float {
+    pub fn new(pole: T) -> DcBlocker<T> {
+        DcBlocker {
+            pole,
+            x1: T::zero(),
+            y1: T::zero()
+        }
+    }
+
+    /// Filters a single sample.
+    pub fn process(&mut self, x: T) -> T {
+        let y = x - self.x1 + self.pole*self.y1;
+        self.x1 = x;
+        self.y1 = y;
+        y
+    }
+
+    /// Clears the filter's memory of previous samples.
+    pub fn reset(&mut self) {
+        self.x1 = T::zero();
+        self.y1 = T::zero();
+    }
+}
+
+impl<T> Default for DcBlocker<T> where T: Float {
+    fn default() -> DcBlocker<T> {
+        DcBlocker::new(T::from_f64(0.995))
+    }
+}
+
+/// Maps a pole or zero of an analog filter, in the s plane in radians
+/// per second, to the z plane with the bilinear transform,
+/// `z = (2fs + s)/(2fs - s)`.
+///
+/// The whole left half of the s plane maps inside the unit circle, so
+/// stable analog filters give stable digital filters. The imaginary
+/// axis maps onto the unit circle, but squashed so that infinite
+/// analog frequency lands on the Nyquist frequency. Use `prewarp` to
+/// compensate for that at an important frequency, like a cutoff.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::bilinear_transform;
+///
+/// let z = bilinear_transform(Complex::new(0.0 as f64, 0.0), 48000.0);
+/// assert_eq!(z, Complex::new(1.0, 0.0));
+/// ```
+pub fn bilinear_transform<T>(analog: Complex<T>, sample_rate: T) -> Complex<T> where T: Float {
+    let two_fs = T::from_f64(2.0) * sample_rate;
+    (analog + two_fs) / (-analog + two_fs)
+}
+
+/// The analog frequency, in Hz, that the bilinear transform maps to
+/// `digital_freq`, `fs/π·tan(π·f/fs)`.
+///
+/// Designing the analog prototype at this frequency puts it in the
+/// right place after the transform. Far below the Nyquist frequency
+/// the warping is small, so this is close to `digital_freq`.
+///
+/// ```
+/// use worthe_signals::dsp::prewarp;
+///
+/// let warped = prewarp(12000.0 as f64, 48000.0);
+/// assert!((warped - 48000.0/std::f64::consts::PI).abs() < 1e-6);
+/// ```
+pub fn prewarp<T>(digital_freq: T, sample_rate: T) -> T where T: Float {
+    sample_rate / T::pi() * (T::pi() * digital_freq / sample_rate).tan()
+}
+
+/// A first order allpass filter, `y[n] = c·x[n] + x[n-1] - c·y[n-1]`.
+///
+/// An allpass filter lets every frequency through at the same level,
+/// but delays them by different amounts. The phase shift goes from 0
+/// at DC to -π at the Nyquist frequency, passing -π/2 at the break
+/// frequency. For a break frequency `fc`, the coefficient is
+/// `c = (tan(π·fc/fs) - 1) / (tan(π·fc/fs) + 1)`, so a coefficient
+/// of 0 puts the break at a quarter of the sample rate and is a plain
+/// one sample delay.
+///
+/// ```
+/// use worthe_signals::dsp::iir::Allpass;
+///
+/// let filter = Allpass::from_break_frequency(1000.0 as f64, 48000.0);
+/// let response = filter.response_at(1000.0, 48000.0);
+/// assert!((response.magnitude() - 1.0).abs() < 1e-9);
+/// assert!((response.angle() + std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allpass<T> {
+    pub coefficient: T,
+    x1: T,
+    y1: T
+}
+
+impl<T> Allpass<T> where T: Float {
+    pub fn new(coefficient: T) -> Allpass<T> {
+        Allpass {
+            coefficient,
+            x1: T::zero(),
+            y1: T::zero()
+        }
+    }
+
+    /// An allpass filter with a phase shift of -π/2 at `break_freq`.
+    pub fn from_break_frequency(break_freq: T, sample_rate: T) -> Allpass<T> {
+        let t = (T::pi() * break_freq / sample_rate).tan();
+        Allpass::new((t - T::one()) / (t + T::one()))
+    }
+
+    /// Filters a single sample.
+    pub fn process(&mut self, x: T) -> T {
+        let y = self.coefficient*x + self.x1 - self.coefficient*self.y1;
+        self.x1 = x;
+        self.y1 = y;
+        y
+    }
+
+    /// Clears the filter's memory of previous samples.
+    pub fn reset(&mut self) {
+        self.x1 = T::zero();
+        self.y1 = T::zero();
+    }
+
+    /// Evaluates the filter's frequency response, H(e^jω), at a
+    /// frequency given in Hz.
+    pub fn response_at(&self, freq: T, sample_rate: T) -> Complex<T> {
+        let omega = T::two_pi() * freq / sample_rate;
+        let z1 = Complex::new(T::zero(), -omega).exp();
+        let c = Complex::new(self.coefficient, T::zero());
+        let one = Complex::new(T::one(), T::zero());
+        (c + z1) / (one + c*z1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+
+    #[test]
+    fn dc_blocker_removes_offset_and_keeps_tone() {
+        use ::dsp::rms;
+        use ::sinusoid::Sinusoid;
+
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 1000.0, 0.0);
+        let input: Vec<f64> = tone.sample_range(0.0, 0.5, 48000.0).iter().map(|x| x + 0.5).collect();
+        let mut blocker = DcBlocker::default();
+        let output: Vec<f64> = input.iter().map(|&x| blocker.process(x)).collect();
+
+        // the last 100 periods, long after the filter has settled
+        let steady = &output[output.len() - 4800..];
+        let mean = steady.iter().sum::<f64>() / steady.len() as f64;
+        assert!(mean.abs() < 1e-3, "Mean was {}", mean);
+        let level = rms(steady);
+        assert!((level - 0.5f64.sqrt()).abs() < 1e-2, "RMS was {}", level);
+    }
+
+    #[test]
+    fn low_pass_response() {
+        let filter: Biquad<f64> = Biquad::low_pass(1000.0, 0.5f64.sqrt(), 48000.0);
+        let dc = filter.response_at(0.0, 48000.0);
+        assert!((dc.magnitude() - 1.0).abs() < 1e-9, "DC response was {:?}", dc);
+
+        let gains: Vec<f64> = [1000.0, 2000.0, 4000.0, 8000.0, 16000.0].iter()
+            .map(|&f| filter.response_at(f, 48000.0).magnitude())
+            .collect();
+        for pair in gains.windows(2) {
+            assert!(pair[1] < pair[0], "Gains should roll off, but were {:?}", gains);
+        }
+        // second order roll off is 12 dB per octave
+        assert!(gains[3] < 0.02, "Gains were {:?}", gains);
+    }
+
+    #[test]
+    fn process_matches_response_for_dc() {
+        let mut filter: Biquad<f64> = Biquad::low_pass(1000.0, 0.5f64.sqrt(), 48000.0);
+        let mut y = 0.0;
+        for _ in 0..2000 {
+            y = filter.process(1.0);
+        }
+        assert!((y - 1.0).abs() < 1e-9);
+        filter.reset();
+        assert_eq!(filter.process(0.0), 0.0);
+    }
+
+    #[test]
+    fn allpass_has_flat_magnitude_and_varying_phase() {
+        let filter: Allpass<f64> = Allpass::from_break_frequency(2000.0, 48000.0);
+        let responses: Vec<Complex<f64>> = (0..24)
+            .map(|k| filter.response_at(k as f64 * 1000.0, 48000.0))
+            .collect();
+        for response in &responses {
+            assert!((response.magnitude() - 1.0).abs() < 1e-9, "Response was {:?}", response);
+        }
+        let phases: Vec<f64> = responses.iter().map(|r| r.angle()).collect();
+        assert!(phases[0].abs() < 1e-9);
+        assert!((phases[2] + std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+        for pair in phases.windows(2) {
+            assert!(pair[1] < pair[0], "Phase should fall, but was {:?}", phases);
+        }
+    }
+
+    #[test]
+    fn allpass_impulse_response_keeps_energy() {
+        let mut filter: Allpass<f64> = Allpass::new(-0.6);
+        let energy: f64 = (0..200)
+            .map(|n| filter.process(if n == 0 { 1.0 } else { 0.0 }))
+            .map(|y| y*y)
+            .sum();
+        assert!((energy - 1.0).abs() < 1e-9, "Energy was {}", energy);
+        filter.reset();
+        assert_eq!(filter.process(0.0), 0.0);
+    }
+
+    #[test]
+    fn bilinear_transform_keeps_stable_poles_stable() {
+        for &(real, imag) in &[(-1.0, 0.0), (-100.0, 5000.0), (-20000.0, -30000.0), (-1e-3, 1e6)] {
+            let z = bilinear_transform(Complex::new(real, imag), 48000.0_f64);
+            assert!(z.magnitude() < 1.0, "Pole {} + {}j mapped to {:?}", real, imag, z);
+        }
+        let on_axis = bilinear_transform(Complex::new(0.0, 2.0 * std::f64::consts::PI * 1000.0), 48000.0_f64);
+        assert!(on_axis.magnitude().approx_eq(1.0));
+        let unstable = bilinear_transform(Complex::new(10.0, 0.0), 48000.0_f64);
+        assert!(unstable.magnitude() > 1.0);
+    }
+
+    #[test]
+    fn prewarp_is_nearly_identity_at_low_frequencies() {
+        let warped = prewarp(100.0_f64, 48000.0);
+        assert!((warped - 100.0).abs() / 100.0 < 1e-4, "Warped to {}", warped);
+        let warped_high = prewarp(20000.0_f64, 48000.0);
+        assert!(warped_high > 20000.0 * 1.5);
+    }
+}
@@ -0,0 +1,31 @@
+pub mod db;
+pub mod fir;
+pub mod iir;
+pub mod noise;
+mod analysis;
+mod buffer;
+mod complex_ops;
+mod convolution;
+mod delay;
+mod effects;
+mod fourier;
+mod generators;
+mod goertzel;
+mod music;
+mod poly;
+mod spectral;
+
+pub use self::fir::{filtfilt, freq_response, group_delay};
+pub use self::iir::{bilinear_transform, prewarp};
+pub use self::analysis::*;
+pub use self::buffer::*;
+pub use self::complex_ops::*;
+pub use self::convolution::*;
+pub use self::delay::*;
+pub use self::effects::*;
+pub use self::fourier::*;
+pub use self::generators::*;
+pub use self::goertzel::*;
+pub use self::music::*;
+pub use self::poly::*;
+pub use self::spectral::*;
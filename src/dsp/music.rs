@@ -0,0 +1,17 @@
+use ::num_traits::Float;
+
+/// Converts a frequency in Hz to a MIDI note number.
+///
+/// MIDI note 69 is A4, tuned to 440 Hz, and each semitone is one note
+/// number. The result is not rounded, so frequencies between notes
+/// give fractional note numbers.
+///
+/// ```
+/// use worthe_signals::dsp::frequency_to_midi;
+///
+/// assert!((frequency_to_midi(440.0 as f32) - 69.0).abs() < 0.0001);
+/// assert!((frequency_to_midi(261.6256 as f32) - 60.0).abs() < 0.0001);
+/// ```
+pub fn frequency_to_midi<T>(freq: T) -> T where T: Float {
+    T::from_f64(69.0) + T::from_f64(12.0)*(freq / T::from_f64(440.0)).log2()
+}
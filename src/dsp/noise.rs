@@ -0,0 +1,111 @@
+use ::num_traits::Float;
+
+/// A small xorshift pseudo random number generator. It isn't suitable
+/// for anything cryptographic, but it is fast and gives the same
+/// sequence for the same seed, which is what test signals need.
+struct Rng {
+    state: u64
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // xorshift gets stuck at zero, so the seed is mixed with a
+        // constant to keep a seed of zero usable.
+        Rng { state: seed ^ 0x2545_F491_4F6C_DD1D }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A uniformly distributed value in [0, 1)
+    fn next_unit<T>(&mut self) -> T where T: Float {
+        let bits = 24;
+        let value = (self.next_u64() >> (64 - bits)) as usize;
+        T::from_usize(value) / T::from_usize(1 << bits)
+    }
+}
+
+/// Generates uniformly distributed white noise in the range
+/// [-amplitude, amplitude).
+///
+/// The same seed always produces the same samples.
+///
+/// ```
+/// use worthe_signals::dsp::noise;
+///
+/// let a: Vec<f64> = noise::white(100, 0.5, 42);
+/// let b: Vec<f64> = noise::white(100, 0.5, 42);
+/// assert_eq!(a, b);
+/// assert!(a.iter().all(|&x| x >= -0.5 && x < 0.5));
+/// ```
+pub fn white<T>(len: usize, amplitude: T, seed: u64) -> Vec<T> where T: Float {
+    let mut rng = Rng::new(seed);
+    let two = T::from_f64(2.0);
+    (0..len)
+        .map(|_| (rng.next_unit::<T>()*two - T::one()) * amplitude)
+        .collect()
+}
+
+/// Generates pink noise, whose power falls off in proportion to 1/f,
+/// using the Voss-McCartney algorithm.
+///
+/// The output is the average of 16 rows of random values, plus one
+/// more that changes every sample. Row `k` only changes every
+/// `2^(k+1)` samples, so each row adds power an octave lower than the
+/// one before it. The result stays in the range
+/// [-amplitude, amplitude).
+///
+/// The same seed always produces the same samples.
+///
+/// ```
+/// use worthe_signals::dsp::noise;
+///
+/// let a: Vec<f64> = noise::pink(100, 0.5, 42);
+/// assert_eq!(a, noise::pink(100, 0.5, 42));
+/// assert!(a.iter().all(|&x| x >= -0.5 && x < 0.5));
+/// ```
+pub fn pink<T>(len: usize, amplitude: T, seed: u64) -> Vec<T> where T: Float {
+    let mut rng = Rng::new(seed);
+    let two = T::from_f64(2.0);
+    let mut next = || rng.next_unit::<T>()*two - T::one();
+
+    let mut rows: Vec<T> = (0..16).map(|_| next()).collect();
+    let scale = amplitude / T::from_usize(rows.len() + 1);
+    (1..len + 1)
+        .map(|n| {
+            let row = n.trailing_zeros() as usize;
+            if row < rows.len() {
+                rows[row] = next();
+            }
+            let sum = rows.iter().fold(next(), |acc, &x| acc + x);
+            sum*scale
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::dsp::{hann_window, welch_psd};
+
+    #[test]
+    fn pink_noise_power_falls_with_frequency() {
+        let samples: Vec<f64> = pink(1 << 14, 1.0, 3);
+        let psd = welch_psd(&samples, &hann_window(256), 128);
+        let average = |bins: &[f64]| bins.iter().sum::<f64>() / bins.len() as f64;
+        // bins 1 to 4 are around 40 times lower in frequency than bins
+        // 64 to 128
+        let low = average(&psd[1..5]);
+        let high = average(&psd[64..129]);
+        assert!(low > 10.0*high, "Low bins had {}, high bins had {}", low, high);
+
+        // for comparison, white noise is flat
+        let white: Vec<f64> = white(1 << 14, 1.0, 3);
+        let psd = welch_psd(&white, &hann_window(256), 128);
+        assert!(average(&psd[1..5]) < 2.0*average(&psd[64..129]));
+    }
+}
@@ -0,0 +1,202 @@
+use ::complex::Complex;
+use ::num_traits::Float;
+
+/// Evaluates a polynomial at `z` using Horner's method.
+///
+/// The coefficients are in order of decreasing power, so `coeffs[0]`
+/// is the coefficient of the highest power and the last coefficient
+/// is the constant term. A polynomial with no coefficients is 0
+/// everywhere.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::eval_poly;
+///
+/// // z² + 1 at z = i
+/// let coeffs = [Complex::new(1.0 as f64, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)];
+/// assert_eq!(eval_poly(&coeffs, Complex::new(0.0, 1.0)), Complex::new(0.0, 0.0));
+/// ```
+pub fn eval_poly<T>(coeffs: &[Complex<T>], z: Complex<T>) -> Complex<T> where T: Float {
+    coeffs.iter().fold(Complex::new(T::zero(), T::zero()), |acc, &c| acc*z + c)
+}
+
+/// Multiplies out `Π(z - root)` into a monic polynomial's
+/// coefficients, in the same decreasing power order that `eval_poly`
+/// takes.
+///
+/// The result has one more coefficient than there are roots, and is
+/// just `[1]` if there are no roots.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::poly_from_roots;
+///
+/// let coeffs = poly_from_roots(&[Complex::new(1.0 as f64, 0.0), Complex::new(2.0, 0.0)]);
+/// assert_eq!(coeffs, vec![Complex::new(1.0, 0.0), Complex::new(-3.0, 0.0), Complex::new(2.0, 0.0)]);
+/// ```
+pub fn poly_from_roots<T>(roots: &[Complex<T>]) -> Vec<Complex<T>> where T: Float {
+    let mut coeffs = vec![Complex::new(T::one(), T::zero())];
+    for &root in roots {
+        // multiplying by (z - root) shifts every power up by one and
+        // subtracts root times the old coefficients
+        coeffs.push(Complex::new(T::zero(), T::zero()));
+        for i in (1..coeffs.len()).rev() {
+            coeffs[i] = coeffs[i] - root*coeffs[i-1];
+        }
+    }
+    coeffs
+}
+
+/// The two roots of `az² + bz + c`, from the quadratic formula.
+///
+/// The formula is rearranged so that the two roots don't lose
+/// precision when one is much smaller than the other. A repeated root
+/// is returned twice. `a` must not be 0, since then it isn't a
+/// quadratic, and the roots come out as infinite or NaN.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::roots_quadratic;
+///
+/// let one = Complex::new(1.0 as f64, 0.0);
+/// let zero = Complex::new(0.0, 0.0);
+/// let roots = roots_quadratic(one, zero, -one);
+/// assert!((roots[0] - one).magnitude() < 1e-12 || (roots[0] + one).magnitude() < 1e-12);
+/// assert!((roots[0] + roots[1]).magnitude() < 1e-12);
+/// ```
+pub fn roots_quadratic<T>(a: Complex<T>, b: Complex<T>, c: Complex<T>) -> [Complex<T>; 2] where T: Float {
+    let zero = Complex::new(T::zero(), T::zero());
+    let four = Complex::new(T::from_f64(4.0), T::zero());
+    let half = Complex::new(T::from_f64(0.5), T::zero());
+
+    let discriminant = (b*b - four*a*c).sqrt();
+    let (plus, minus) = (b + discriminant, b - discriminant);
+    let larger = if plus.magnitude() >= minus.magnitude() { plus } else { minus };
+    let q = -(larger*half);
+    if q == zero {
+        [zero, zero]
+    } else {
+        [q/a, c/q]
+    }
+}
+
+/// The three roots of `az³ + bz² + cz + d`, from Cardano's formula.
+///
+/// A repeated root is returned as many times as it repeats, although
+/// rounding can split repeated roots apart slightly. `a` must not be
+/// 0, since then it isn't a cubic, and the roots come out as infinite
+/// or NaN.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::{roots_cubic, eval_poly};
+///
+/// let real = |x: f64| Complex::new(x, 0.0);
+/// // (z - 1)(z - 2)(z - 3)
+/// let coeffs = [real(1.0), real(-6.0), real(11.0), real(-6.0)];
+/// for root in &roots_cubic(coeffs[0], coeffs[1], coeffs[2], coeffs[3]) {
+///     assert!(eval_poly(&coeffs, *root).magnitude() < 1e-9);
+/// }
+/// ```
+pub fn roots_cubic<T>(a: Complex<T>, b: Complex<T>, c: Complex<T>, d: Complex<T>) -> [Complex<T>; 3] where T: Float {
+    let zero = Complex::new(T::zero(), T::zero());
+    let real = |x: f64| Complex::new(T::from_f64(x), T::zero());
+
+    // substituting z = t - b/3a gives the depressed cubic t³ + pt + q
+    let shift = b / (real(3.0)*a);
+    let p = (real(3.0)*a*c - b*b) / (real(3.0)*a*a);
+    let q = (real(2.0)*b*b*b - real(9.0)*a*b*c + real(27.0)*a*a*d) / (real(27.0)*a*a*a);
+
+    let discriminant = (q*q/real(4.0) + p*p*p/real(27.0)).sqrt();
+    let minus_half_q = -(q/real(2.0));
+    let plus = (minus_half_q + discriminant).cbrt();
+    let minus = (minus_half_q - discriminant).cbrt();
+    let u = if plus.magnitude() >= minus.magnitude() { plus } else { minus };
+
+    let rotation = Complex::from_polar(T::one(), T::two_pi() / T::from_f64(3.0));
+    let mut roots = [zero; 3];
+    let mut u_k = u;
+    for root in roots.iter_mut() {
+        let t = if u_k == zero { zero } else { u_k - p/(real(3.0)*u_k) };
+        *root = t - shift;
+        u_k = u_k*rotation;
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::num_traits::ApproxEq;
+
+    #[test]
+    fn eval_poly_matches_expanded_powers() {
+        let coeffs = [Complex::new(2.0, -1.0), Complex::new(0.5, 0.0), Complex::new(-3.0, 2.0), Complex::new(1.0, 1.0)];
+        let z = Complex::new(0.3_f64, -1.2);
+        let expected = coeffs.iter().enumerate()
+            .fold(Complex::new(0.0, 0.0), |acc, (i, &c)| acc + c*z.powi(3 - i as i32));
+        assert!((eval_poly(&coeffs, z) - expected).magnitude() < 1e-12);
+        assert_eq!(eval_poly(&[], z), Complex::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn poly_from_conjugate_roots_is_real() {
+        let i = Complex::new(0.0_f64, 1.0);
+        let coeffs = poly_from_roots(&[i, -i]);
+        let expected = [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)];
+        for (a, b) in coeffs.iter().zip(expected.iter()) {
+            assert!((*a - *b).magnitude() < 1e-12);
+        }
+
+        let pole = Complex::from_polar(0.9_f64, 0.7);
+        let coeffs = poly_from_roots(&[pole, pole.conjugate(), Complex::new(-0.5, 0.0)]);
+        assert_eq!(coeffs.len(), 4);
+        for c in &coeffs {
+            assert!(c.imag.approx_eq(0.0), "Coefficients were {:?}", coeffs);
+        }
+        for &root in &[pole, pole.conjugate(), Complex::new(-0.5, 0.0)] {
+            assert!(eval_poly(&coeffs, root).magnitude() < 1e-12);
+        }
+    }
+
+    fn assert_roots(mut roots: Vec<Complex<f64>>, mut expected: Vec<Complex<f64>>) {
+        let key = |z: &Complex<f64>| (z.real * 1e6).round() as i64 * 1_000_000_000 + (z.imag * 1e6).round() as i64;
+        roots.sort_by_key(key);
+        expected.sort_by_key(key);
+        for (a, b) in roots.iter().zip(expected.iter()) {
+            assert!((*a - *b).magnitude() < 1e-6, "Roots were {:?}, expected {:?}", roots, expected);
+        }
+    }
+
+    #[test]
+    fn quadratic_roots() {
+        let real = |x: f64| Complex::new(x, 0.0);
+        let i = Complex::new(0.0, 1.0);
+        assert_roots(roots_quadratic(real(1.0), real(0.0), real(-1.0)).to_vec(), vec![real(1.0), real(-1.0)]);
+        assert_roots(roots_quadratic(real(1.0), real(0.0), real(1.0)).to_vec(), vec![i, -i]);
+        assert_roots(roots_quadratic(real(2.0), real(-4.0), real(2.0)).to_vec(), vec![real(1.0), real(1.0)]);
+        assert_roots(roots_quadratic(real(1.0), real(0.0), real(0.0)).to_vec(), vec![real(0.0), real(0.0)]);
+        // very different sizes of root keep their precision
+        let roots = roots_quadratic(real(1.0), real(-1e8), real(1.0));
+        assert!(roots.iter().any(|r| (r.real - 1e-8).abs() < 1e-20));
+    }
+
+    #[test]
+    fn cubic_roots() {
+        let real = |x: f64| Complex::new(x, 0.0);
+        let i = Complex::new(0.0, 1.0);
+        // z³ - z = z(z - 1)(z + 1)
+        assert_roots(roots_cubic(real(1.0), real(0.0), real(-1.0), real(0.0)).to_vec(), vec![real(0.0), real(1.0), real(-1.0)]);
+        // (z - 2)(z² + 1)
+        assert_roots(roots_cubic(real(1.0), real(-2.0), real(1.0), real(-2.0)).to_vec(), vec![real(2.0), i, -i]);
+        // (z + 1)³
+        assert_roots(roots_cubic(real(1.0), real(3.0), real(3.0), real(1.0)).to_vec(), vec![real(-1.0); 3]);
+
+        let expected = vec![Complex::new(0.5, -1.0), Complex::new(-2.0, 0.3), Complex::new(1.0, 1.0)];
+        let mut coeffs = poly_from_roots(&expected);
+        for c in coeffs.iter_mut() {
+            *c = *c * Complex::new(0.0, 3.0);
+        }
+        assert_roots(roots_cubic(coeffs[0], coeffs[1], coeffs[2], coeffs[3]).to_vec(), expected);
+    }
+}
@@ -0,0 +1,781 @@
+use std::ops::Range;
+use ::complex::Complex;
+use ::num_traits::Float;
+use super::{fft, forward_any_length, rfft, irfft};
+use super::db::amplitude_to_db;
+
+/// A periodic Hann window, `0.5 - 0.5·cos(2πn/N)`.
+///
+/// The periodic form is the one to use for spectral analysis, since
+/// it tiles smoothly when repeated every N samples.
+///
+/// ```
+/// use worthe_signals::dsp::hann_window;
+/// use worthe_signals::num_traits::ApproxEq;
+///
+/// let window: Vec<f64> = hann_window(4);
+/// assert!(window.iter().zip(&[0.0, 0.5, 1.0, 0.5]).all(|(a, &b)| a.approx_eq(b)));
+/// ```
+pub fn hann_window<T>(len: usize) -> Vec<T> where T: Float {
+    let half = T::from_f64(0.5);
+    (0..len)
+        .map(|n| half - half*(T::two_pi()*T::from_usize(n)/T::from_usize(len)).cos())
+        .collect()
+}
+
+/// Estimates the power spectral density of a signal using Welch's
+/// method.
+///
+/// The signal is split into segments the same length as `window`,
+/// with consecutive segments sharing `overlap` samples. Each segment
+/// is multiplied by the window and transformed, and the resulting
+/// periodograms are averaged together. Any samples left over after
+/// the last full segment are ignored. A power of two window length
+/// lets each segment use `fft`.
+///
+/// Each bin is normalized as `|X[k]|² / Σw[n]²`, so white noise with
+/// variance σ² has a PSD of approximately σ² in every bin, regardless
+/// of the window used. Only the non-negative frequencies, bins 0 to
+/// N/2 inclusive, are returned. The negative frequencies of a real
+/// signal are a mirror image of these, and are not folded in.
+///
+/// If the signal is shorter than the window, there are no segments to
+/// average and an empty vector is returned.
+///
+/// # Panics
+///
+/// Panics if the window is empty, or if `overlap` is not less than
+/// the window's length.
+pub fn welch_psd<T>(samples: &[T], window: &[T], overlap: usize) -> Vec<T> where T: Float {
+    let bins = window.len() / 2 + 1;
+    let window_power = window.iter().fold(T::zero(), |acc, &w| acc + w*w);
+
+    let mut psd = vec![T::zero(); bins];
+    let segments = for_each_segment(samples.len(), window.len(), overlap, |segment| {
+        let spectrum = segment_spectrum(&samples[segment], window);
+        for (p, &a) in psd.iter_mut().zip(spectrum.iter()) {
+            *p = *p + (a*a.conjugate()).real / window_power;
+        }
+    });
+
+    if segments == 0 {
+        return Vec::new();
+    }
+    let segments = T::from_usize(segments);
+    psd.into_iter().map(|p| p / segments).collect()
+}
+
+/// Estimates the cross spectral density of two signals using Welch's
+/// method.
+///
+/// Segments are taken and windowed as in `welch_psd`, up to the end
+/// of the shorter signal, and each bin is the average of
+/// `X[k]·conj(Y[k]) / Σw[n]²`. Only the non-negative frequencies,
+/// bins 0 to N/2 inclusive, are returned. The cross spectrum of a
+/// signal with itself is its power spectral density.
+///
+/// If the shorter signal is shorter than the window, an empty vector
+/// is returned.
+///
+/// # Panics
+///
+/// Panics if the window is empty, or if `overlap` is not less than
+/// the window's length.
+pub fn cross_spectrum<T>(x: &[T], y: &[T], window: &[T], overlap: usize) -> Vec<Complex<T>> where T: Float {
+    let bins = window.len() / 2 + 1;
+    let window_power = window.iter().fold(T::zero(), |acc, &w| acc + w*w);
+
+    let mut cross = vec![Complex::new(T::zero(), T::zero()); bins];
+    let segments = for_each_segment_spectrum(x, y, window, overlap, |x_spectrum, y_spectrum| {
+        for (c, (&a, &b)) in cross.iter_mut().zip(x_spectrum.iter().zip(y_spectrum)) {
+            *c = *c + (a*b.conjugate()).map(|part| part / window_power);
+        }
+    });
+
+    if segments == 0 {
+        return Vec::new();
+    }
+    let segments = T::from_usize(segments);
+    cross.into_iter().map(|c| c.map(|part| part / segments)).collect()
+}
+
+/// Splits `x` and `y` into the windowed segments used by Welch's
+/// method, up to the end of the shorter signal, and calls `f` with
+/// the non-negative frequency bins of each pair of segments. Returns
+/// the number of segments.
+fn for_each_segment_spectrum<T, F>(x: &[T], y: &[T], window: &[T], overlap: usize, mut f: F) -> usize
+    where T: Float, F: FnMut(&[Complex<T>], &[Complex<T>])
+{
+    let len = x.len().min(y.len());
+    for_each_segment(len, window.len(), overlap, |segment| {
+        let x_spectrum = segment_spectrum(&x[segment.clone()], window);
+        let y_spectrum = segment_spectrum(&y[segment], window);
+        f(&x_spectrum, &y_spectrum);
+    })
+}
+
+/// Calls `f` with the range of each Welch segment of a signal of
+/// length `len`, with consecutive segments sharing `overlap` samples.
+/// Returns the number of segments.
+fn for_each_segment<F>(len: usize, segment_len: usize, overlap: usize, mut f: F) -> usize where F: FnMut(Range<usize>) {
+    assert!(segment_len > 0, "Welch's method needs a non-empty window");
+    assert!(overlap < segment_len, "Overlap ({}) must be less than the window length ({})", overlap, segment_len);
+
+    let hop = segment_len - overlap;
+    let mut segments = 0;
+    let mut start = 0;
+    while start + segment_len <= len {
+        f(start..start+segment_len);
+        segments += 1;
+        start += hop;
+    }
+    segments
+}
+
+/// Multiplies a segment by the window and returns the non-negative
+/// frequency bins of its transform.
+fn segment_spectrum<T>(segment: &[T], window: &[T]) -> Vec<Complex<T>> where T: Float {
+    let windowed: Vec<Complex<T>> = segment.iter()
+        .zip(window.iter())
+        .map(|(&s, &w)| Complex::new(s*w, T::zero()))
+        .collect();
+    let mut spectrum = forward_any_length(&windowed);
+    spectrum.truncate(window.len() / 2 + 1);
+    spectrum
+}
+
+/// Estimates the magnitude squared coherence between two signals,
+/// `|Sxy|² / (Sxx·Syy)`, for each non-negative frequency bin.
+///
+/// The cross and auto spectra are Welch averaged, with segments taken
+/// as in `welch_psd`. A value near 1 means that `y` is linearly
+/// related to `x` at that frequency, and a value near 0 means that
+/// they are unrelated. Bins where either signal has no energy are
+/// zero. Averaging over more segments lowers the coherence of
+/// unrelated signals; with a single segment, every bin is 1.
+///
+/// If the shorter signal is shorter than the window, an empty vector
+/// is returned.
+///
+/// # Panics
+///
+/// Panics if the window is empty, or if `overlap` is not less than
+/// the window's length.
+pub fn coherence<T>(x: &[T], y: &[T], window: &[T], overlap: usize) -> Vec<T> where T: Float {
+    let bins = window.len() / 2 + 1;
+    let mut sxy = vec![Complex::new(T::zero(), T::zero()); bins];
+    let mut sxx = vec![T::zero(); bins];
+    let mut syy = vec![T::zero(); bins];
+    // the window power and segment count normalizations cancel out of
+    // the ratio, so the spectra are left as sums
+    let segments = for_each_segment_spectrum(x, y, window, overlap, |x_spectrum, y_spectrum| {
+        for (k, (&a, &b)) in x_spectrum.iter().zip(y_spectrum).enumerate() {
+            sxy[k] = sxy[k] + a*b.conjugate();
+            sxx[k] = sxx[k] + (a*a.conjugate()).real;
+            syy[k] = syy[k] + (b*b.conjugate()).real;
+        }
+    });
+
+    if segments == 0 {
+        return Vec::new();
+    }
+    sxy.into_iter().zip(sxx.into_iter().zip(syy))
+        .map(|(c, (a, b))| {
+            let power = a*b;
+            if power > T::zero() {
+                (c*c.conjugate()).real / power
+            } else {
+                T::zero()
+            }
+        })
+        .collect()
+}
+
+/// Estimates the frequency response of a linear system from its input
+/// and output, using the H1 estimator `H[k] = Syx[k] / Sxx[k]`.
+///
+/// The spectra are Welch averaged, with segments taken as in
+/// `welch_psd`, and only the non-negative frequencies are returned.
+/// Bins where the input has no energy are zero. Noise on the output
+/// averages out of the estimate, but noise on the input biases its
+/// magnitude low.
+///
+/// If the shorter signal is shorter than the window, an empty vector
+/// is returned.
+///
+/// # Panics
+///
+/// Panics if the window is empty, or if `overlap` is not less than
+/// the window's length.
+pub fn estimate_transfer_function<T>(input: &[T], output: &[T], window: &[T], overlap: usize) -> Vec<Complex<T>> where T: Float {
+    let bins = window.len() / 2 + 1;
+    let mut syx = vec![Complex::new(T::zero(), T::zero()); bins];
+    let mut sxx = vec![T::zero(); bins];
+    // as in coherence, the normalizations cancel out of the ratio
+    let segments = for_each_segment_spectrum(input, output, window, overlap, |x_spectrum, y_spectrum| {
+        for (k, (&a, &b)) in x_spectrum.iter().zip(y_spectrum).enumerate() {
+            syx[k] = syx[k] + b*a.conjugate();
+            sxx[k] = sxx[k] + (a*a.conjugate()).real;
+        }
+    });
+
+    if segments == 0 {
+        return Vec::new();
+    }
+    syx.into_iter().zip(sxx)
+        .map(|(c, a)| {
+            if a > T::zero() {
+                c.map(|part| part / a)
+            } else {
+                Complex::new(T::zero(), T::zero())
+            }
+        })
+        .collect()
+}
+
+/// The one sided amplitude spectrum of a signal in dB, for plotting.
+///
+/// The samples are multiplied by a Hann window and zero padded up to
+/// the next power of two before taking their FFT. The magnitudes are
+/// scaled so that a tone with amplitude A reads as approximately
+/// `20·log10(A)` dB at its peak.
+///
+/// Returns the frequency of each bin, from 0 Hz to Nyquist inclusive,
+/// along with its magnitude in dB. Bins with no energy are negative
+/// infinity. An empty input gives empty vectors, and a single sample
+/// isn't windowed, since there's nothing to taper.
+///
+/// ```
+/// use worthe_signals::dsp::spectrum_db;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// let tone = Sinusoid::new(0.1 as f64, 1000.0, 0.0);
+/// let (frequencies, magnitudes) = spectrum_db(&tone.sample_range(0.0, 0.128, 8000.0), 8000.0);
+/// assert_eq!(frequencies.len(), 513);
+/// assert_eq!(frequencies[128], 1000.0);
+/// assert!((magnitudes[128] + 20.0).abs() < 0.01);
+/// ```
+pub fn spectrum_db<T>(samples: &[T], sample_rate: T) -> (Vec<T>, Vec<T>) where T: Float {
+    if samples.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+
+    let len = samples.len().next_power_of_two();
+    // a Hann window of one sample is zero, which would leave nothing
+    let window = if samples.len() == 1 { vec![T::one()] } else { hann_window(samples.len()) };
+    let window_sum = window.iter().fold(T::zero(), |acc, &w| acc + w);
+    let mut padded: Vec<Complex<T>> = samples.iter().zip(window)
+        .map(|(&x, w)| Complex::new(x*w, T::zero()))
+        .collect();
+    padded.resize(len, Complex::new(T::zero(), T::zero()));
+    let spectrum = fft(&padded).expect("padded to a power of two");
+
+    let bins = len/2 + 1;
+    let two = T::from_f64(2.0);
+    let frequencies = (0..bins).map(|k| bin_frequency(k, bins, sample_rate)).collect();
+    let magnitudes = spectrum.into_iter().take(bins).enumerate().map(|(k, x)| {
+        // the negative frequencies are folded into the positive ones,
+        // except at DC and Nyquist where they're the same bin.
+        let scale = if k == 0 || k == len/2 { T::one() } else { two };
+        amplitude_to_db(scale*x.magnitude()/window_sum)
+    }).collect();
+    (frequencies, magnitudes)
+}
+
+/// The frequency of a bin in a one sided spectrum with `bins` bins,
+/// running from 0 to Nyquist inclusive.
+fn bin_frequency<T>(k: usize, bins: usize, sample_rate: T) -> T where T: Float {
+    if bins < 2 {
+        return T::zero();
+    }
+    let two = T::from_f64(2.0);
+    T::from_usize(k) * sample_rate / (two*T::from_usize(bins - 1))
+}
+
+/// The magnitude weighted mean frequency of a spectrum, which
+/// describes how bright a sound is.
+///
+/// The spectrum is one sided, running from 0 Hz to Nyquist inclusive,
+/// like the output of `welch_psd`. A spectrum with no energy has no
+/// centroid, and gives 0.
+///
+/// ```
+/// use worthe_signals::dsp::spectral_centroid;
+///
+/// // 5 bins from 0 to 4000 Hz, so each bin is 1000 Hz apart
+/// let spectrum = [0.0 as f32, 1.0, 0.0, 1.0, 0.0];
+/// assert_eq!(spectral_centroid(&spectrum, 8000.0), 2000.0);
+/// ```
+pub fn spectral_centroid<T>(magnitude: &[T], sample_rate: T) -> T where T: Float {
+    let total = magnitude.iter().fold(T::zero(), |acc, &m| acc + m);
+    if total == T::zero() {
+        return T::zero();
+    }
+    let weighted = magnitude.iter().enumerate().fold(T::zero(), |acc, (k, &m)| {
+        acc + m*bin_frequency(k, magnitude.len(), sample_rate)
+    });
+    weighted / total
+}
+
+/// The frequency below which `percentile` of a spectrum's energy lies.
+///
+/// `percentile` is a fraction between 0 and 1, with 0.85 being a
+/// common choice. The spectrum is one sided, running from 0 Hz to
+/// Nyquist inclusive, and the result is the frequency of the first bin
+/// where the running total of the energy reaches the percentile.
+///
+/// ```
+/// use worthe_signals::dsp::spectral_rolloff;
+///
+/// // 5 bins from 0 to 4000 Hz, so each bin is 1000 Hz apart
+/// let spectrum = [2.0 as f32, 1.0, 1.0, 1.0, 1.0];
+/// assert_eq!(spectral_rolloff(&spectrum, 8000.0, 0.5), 0.0);
+/// assert_eq!(spectral_rolloff(&spectrum, 8000.0, 0.75), 2000.0);
+/// ```
+pub fn spectral_rolloff<T>(magnitude: &[T], sample_rate: T, percentile: T) -> T where T: Float {
+    let total = magnitude.iter().fold(T::zero(), |acc, &m| acc + m*m);
+    let threshold = total*percentile;
+    let mut energy = T::zero();
+    for (k, &m) in magnitude.iter().enumerate() {
+        energy = energy + m*m;
+        if energy >= threshold {
+            return bin_frequency(k, magnitude.len(), sample_rate);
+        }
+    }
+    bin_frequency(magnitude.len().saturating_sub(1), magnitude.len(), sample_rate)
+}
+
+/// How noise-like a spectrum is, as the geometric mean of the power
+/// spectrum over its arithmetic mean.
+///
+/// A flat spectrum, like white noise, gives a flatness near 1, and a
+/// spectrum dominated by a few tones gives a flatness near 0. Any bin
+/// with no energy makes the geometric mean, and so the flatness, 0. A
+/// spectrum with no energy at all gives 0.
+///
+/// ```
+/// use worthe_signals::dsp::spectral_flatness;
+///
+/// assert!((spectral_flatness(&[2.0 as f32, 2.0, 2.0]) - 1.0).abs() < 1e-6);
+/// assert_eq!(spectral_flatness(&[2.0 as f32, 0.0, 2.0]), 0.0);
+/// ```
+pub fn spectral_flatness<T>(magnitude: &[T]) -> T where T: Float {
+    if magnitude.is_empty() {
+        return T::zero();
+    }
+    let bins = T::from_usize(magnitude.len());
+    let arithmetic_mean = magnitude.iter().fold(T::zero(), |acc, &m| acc + m*m) / bins;
+    if arithmetic_mean == T::zero() {
+        return T::zero();
+    }
+    let mean_log = magnitude.iter().fold(T::zero(), |acc, &m| acc + (m*m).ln()) / bins;
+    mean_log.exp() / arithmetic_mean
+}
+
+/// How much of a signal's energy is in its strongest tone, from 0
+/// for no tone at all to 1 for a single pure tone.
+///
+/// The samples are Hann windowed and zero padded to a power of two
+/// before taking their FFT, and the power in the main lobe around the
+/// strongest bin is divided by the total power. The window spreads a
+/// tone's main lobe over 2 bins either side (more with padding), so
+/// that is how much is counted as the tone. Silence, or no samples at
+/// all, gives 0.
+///
+/// ```
+/// use worthe_signals::dsp::tonality;
+/// use worthe_signals::sinusoid::Sinusoid;
+///
+/// let tone = Sinusoid::new(1.0 as f64, 440.0, 0.0).sample_range(0.0, 0.1, 8000.0);
+/// assert!(tonality(&tone) > 0.99);
+/// assert_eq!(tonality(&[0.0 as f64; 100]), 0.0);
+/// ```
+pub fn tonality<T>(samples: &[T]) -> T where T: Float {
+    if samples.is_empty() {
+        return T::zero();
+    }
+
+    let len = samples.len().next_power_of_two();
+    let mut padded: Vec<Complex<T>> = samples.iter().zip(hann_window(samples.len()))
+        .map(|(&x, w)| Complex::new(x*w, T::zero()))
+        .collect();
+    padded.resize(len, Complex::new(T::zero(), T::zero()));
+    let power: Vec<T> = fft(&padded).expect("padded to a power of two").into_iter()
+        .take(len/2 + 1)
+        .map(|x| x.real*x.real + x.imag*x.imag)
+        .collect();
+
+    let total = power.iter().fold(T::zero(), |acc, &p| acc + p);
+    if total == T::zero() {
+        return T::zero();
+    }
+    let peak = power.iter().enumerate()
+        .fold(0, |best, (k, &p)| if p > power[best] { k } else { best });
+    let half_width = (2*len).div_ceil(samples.len());
+    let lobe = power[peak.saturating_sub(half_width)..(peak + half_width + 1).min(power.len())].iter()
+        .fold(T::zero(), |acc, &p| acc + p);
+    lobe / total
+}
+
+/// Scales each bin of a spectrum by a real gain, in place, like a
+/// graphic equalizer.
+///
+/// To keep a real signal real after an inverse transform, the gains
+/// for the negative frequency bins need to match the gains for the
+/// positive frequencies.
+///
+/// # Panics
+///
+/// Panics if there isn't exactly one gain for each bin.
+///
+/// ```
+/// use worthe_signals::complex::Complex;
+/// use worthe_signals::dsp::apply_gain_curve;
+///
+/// let mut spectrum = [Complex::new(1.0 as f32, 1.0), Complex::new(2.0, -2.0)];
+/// apply_gain_curve(&mut spectrum, &[0.5, 0.0]);
+/// assert_eq!(spectrum, [Complex::new(0.5, 0.5), Complex::new(0.0, 0.0)]);
+/// ```
+pub fn apply_gain_curve<T>(spectrum: &mut [Complex<T>], gains: &[T]) where T: Float {
+    assert_eq!(spectrum.len(), gains.len(), "A gain curve needs one gain for each bin");
+    for (x, &gain) in spectrum.iter_mut().zip(gains.iter()) {
+        *x = Complex::new(x.real*gain, x.imag*gain);
+    }
+}
+
+/// Reduces steady background noise in a signal, by subtracting an
+/// estimate of the noise's magnitude spectrum from the signal's
+/// short time spectrum.
+///
+/// Both inputs are split into Hann windowed frames that overlap by
+/// half, each the smallest power of two that lasts at least 20 ms at
+/// `sample_rate`. The average magnitude in each bin over all the
+/// frames of `noise_profile` is the noise estimate, so the profile
+/// should be a recording of just the noise. A profile shorter than a
+/// frame is zero padded to one frame.
+///
+/// The estimate is subtracted from the magnitude of each bin of each
+/// frame of the signal, while keeping the signal's phase. Bins where
+/// the noise estimate is bigger than the signal are set to 0 rather
+/// than going negative. The frames are then transformed back and
+/// added together. The output is the same length as the signal.
+///
+/// Noise only matches its average magnitude on average, so some bins
+/// in some frames are left sticking up above the floor. These come
+/// and go from frame to frame as short, randomly pitched tones, which
+/// is often called musical noise.
+///
+/// ```
+/// use worthe_signals::dsp::spectral_subtract;
+///
+/// let silence = spectral_subtract(&[0.0 as f64; 1000], &[0.5; 500], 8000.0);
+/// assert_eq!(silence.len(), 1000);
+/// assert!(silence.iter().all(|&x| x == 0.0));
+/// ```
+pub fn spectral_subtract<T>(signal: &[T], noise_profile: &[T], sample_rate: T) -> Vec<T> where T: Float {
+    if signal.is_empty() {
+        return Vec::new();
+    }
+
+    let frame_len = (sample_rate*T::from_f64(0.02)).to_usize().max(2).next_power_of_two();
+    let hop = frame_len / 2;
+    let window: Vec<T> = hann_window(frame_len);
+    let frame_spectrum = |frame: &[T]| {
+        let windowed: Vec<T> = frame.iter().zip(window.iter()).map(|(&x, &w)| x*w).collect();
+        rfft(&windowed).expect("frames are a power of two long")
+    };
+
+    let mut noise: Vec<T> = noise_profile.to_vec();
+    if noise.len() < frame_len {
+        noise.resize(frame_len, T::zero());
+    }
+    let noise_frames = (noise.len() - frame_len) / hop + 1;
+    let mut noise_magnitude = vec![T::zero(); frame_len/2 + 1];
+    for start in (0..noise_frames).map(|i| i*hop) {
+        for (total, x) in noise_magnitude.iter_mut().zip(frame_spectrum(&noise[start..start + frame_len])) {
+            *total = *total + x.magnitude();
+        }
+    }
+    for total in noise_magnitude.iter_mut() {
+        *total = *total / T::from_usize(noise_frames);
+    }
+
+    // Padding by a hop on either side means every signal sample is in
+    // two frames, where the Hann windows add up to exactly 1.
+    let mut padded = vec![T::zero(); hop];
+    padded.extend_from_slice(signal);
+    padded.resize(((signal.len() - 1)/hop + 3)*hop, T::zero());
+
+    let mut output = vec![T::zero(); padded.len()];
+    for start in (0..(padded.len() - frame_len)/hop + 1).map(|i| i*hop) {
+        let cleaned: Vec<Complex<T>> = frame_spectrum(&padded[start..start + frame_len]).into_iter()
+            .zip(noise_magnitude.iter())
+            .map(|(x, &n)| {
+                let magnitude = x.magnitude();
+                if magnitude > n {
+                    let scale = (magnitude - n) / magnitude;
+                    Complex::new(x.real*scale, x.imag*scale)
+                } else {
+                    Complex::new(T::zero(), T::zero())
+                }
+            })
+            .collect();
+        let frame = irfft(&cleaned, frame_len).expect("frames are a power of two long");
+        for (y, x) in output[start..start + frame_len].iter_mut().zip(frame) {
+            *y = *y + x;
+        }
+    }
+    output[hop..hop + signal.len()].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::dsp::noise;
+    use ::sinusoid::Sinusoid;
+
+    #[test]
+    fn white_noise_psd_is_flat() {
+        let samples: Vec<f64> = noise::white(8192, 1.0, 1234);
+        let window = vec![1.0; 32];
+        let psd = welch_psd(&samples, &window, 16);
+        assert_eq!(psd.len(), 17);
+
+        // uniform noise on [-1, 1) has a variance of 1/3
+        let expected = 1.0/3.0;
+        for (k, p) in psd.iter().enumerate() {
+            assert!((p - expected).abs() < 0.3*expected, "Bin {} was {}", k, p);
+        }
+    }
+
+    #[test]
+    fn tone_psd_peaks_at_its_bin() {
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 8.0, 0.3);
+        let samples = tone.sample_range(0.0, 16.0, 64.0);
+        let window = vec![1.0; 64];
+        let psd = welch_psd(&samples, &window, 32);
+        assert_eq!(psd.len(), 33);
+
+        let peak = psd.iter().enumerate()
+            .fold(0, |best, (k, &p)| if p > psd[best] { k } else { best });
+        assert_eq!(peak, 8);
+        for (k, p) in psd.iter().enumerate().filter(|&(k, _)| k != 8) {
+            assert!(*p < 1e-6*psd[8], "Bin {} was {}", k, p);
+        }
+    }
+
+    #[test]
+    fn auto_spectrum_matches_psd() {
+        let x: Vec<f64> = noise::white(4096, 1.0, 5);
+        let window = hann_window(128);
+        let auto = cross_spectrum(&x, &x, &window, 64);
+        let psd = welch_psd(&x, &window, 64);
+        assert_eq!(auto.len(), psd.len());
+        for (k, (s, p)) in auto.iter().zip(psd.iter()).enumerate() {
+            assert_eq!(s.imag, 0.0, "Bin {} was {:?}", k, s);
+            assert!(s.real >= 0.0, "Bin {} was {:?}", k, s);
+            assert!((s.real - p).abs() < 1e-9*p, "Bin {} was {:?}, expected {}", k, s, p);
+        }
+    }
+
+    #[test]
+    fn filtered_copy_is_coherent() {
+        use ::dsp::convolve;
+        let x: Vec<f64> = noise::white(8192, 1.0, 99);
+        let y = convolve(&x, &[0.5, 0.3, 0.2]);
+        let window = hann_window(256);
+        let gamma = coherence(&x, &y[..x.len()], &window, 128);
+        assert_eq!(gamma.len(), 129);
+        for (k, g) in gamma.iter().enumerate() {
+            assert!(*g > 0.95 && *g <= 1.0 + 1e-9, "Bin {} was {}", k, g);
+        }
+    }
+
+    #[test]
+    fn independent_noise_is_incoherent() {
+        let x: Vec<f64> = noise::white(8192, 1.0, 99);
+        let y: Vec<f64> = noise::white(8192, 1.0, 4321);
+        let window = hann_window(256);
+        let gamma = coherence(&x, &y, &window, 128);
+        let mean = gamma.iter().sum::<f64>() / gamma.len() as f64;
+        assert!(mean < 0.1, "Mean coherence was {}", mean);
+    }
+
+    #[test]
+    fn transfer_function_of_known_fir() {
+        use ::dsp::{convolve, freq_response};
+        let taps = [0.25, 0.5, -0.3, 0.1];
+        let input: Vec<f64> = noise::white(16384, 1.0, 31);
+        let output = convolve(&input, &taps);
+        let estimate = estimate_transfer_function(&input, &output[..input.len()], &hann_window(256), 128);
+        let expected = freq_response(&taps, 129);
+        assert_eq!(estimate.len(), expected.len());
+        for (k, (h, e)) in estimate.iter().zip(expected).enumerate() {
+            assert!((*h - e).magnitude() < 0.05, "Bin {} was {:?}, expected {:?}", k, h, e);
+        }
+    }
+
+    #[test]
+    fn spectrum_db_of_single_sample() {
+        let (frequencies, magnitudes) = spectrum_db(&[0.5_f64], 8000.0);
+        assert_eq!(frequencies, vec![0.0]);
+        assert!((magnitudes[0] - 20.0*0.5_f64.log10()).abs() < 1e-9, "Magnitude was {}", magnitudes[0]);
+
+        let (_, silent) = spectrum_db(&[0.0_f64], 8000.0);
+        assert_eq!(silent, vec![f64::NEG_INFINITY]);
+    }
+
+    #[test]
+    fn centroid_of_single_peak() {
+        let mut spectrum: Vec<f64> = vec![0.0; 129];
+        spectrum[32] = 3.0;
+        // bins are 44100/256 Hz apart
+        let expected = 32.0 * 44100.0 / 256.0;
+        assert!((spectral_centroid(&spectrum, 44100.0) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn centroid_of_symmetric_peaks() {
+        let mut spectrum: Vec<f64> = vec![0.0; 129];
+        spectrum[20] = 2.0;
+        spectrum[21] = 1.0;
+        spectrum[59] = 1.0;
+        spectrum[60] = 2.0;
+        let expected = 40.0 * 44100.0 / 256.0;
+        assert!((spectral_centroid(&spectrum, 44100.0) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn centroid_of_silence() {
+        assert_eq!(spectral_centroid(&[0.0_f64; 16], 44100.0), 0.0);
+    }
+
+    #[test]
+    fn low_pass_spectrum_has_low_rolloff() {
+        let low_pass: Vec<f64> = (0..129).map(|k| 1.0 / (1.0 + (k as f64 / 8.0).powi(4))).collect();
+        let flat = vec![1.0; 129];
+        let low_rolloff = spectral_rolloff(&low_pass, 44100.0, 0.85);
+        let flat_rolloff = spectral_rolloff(&flat, 44100.0, 0.85);
+        assert!(low_rolloff < 0.1*22050.0, "Low pass rolloff was {}", low_rolloff);
+        assert!(flat_rolloff > 0.8*22050.0, "Flat rolloff was {}", flat_rolloff);
+    }
+
+    #[test]
+    fn white_noise_is_flat_and_tone_is_not() {
+        let samples: Vec<f64> = noise::white(16384, 1.0, 77);
+        let psd = welch_psd(&samples, &vec![1.0; 64], 32);
+        let magnitude: Vec<f64> = psd.iter().map(|p| p.sqrt()).collect();
+        let noise_flatness = spectral_flatness(&magnitude);
+        assert!(noise_flatness > 0.95, "Noise flatness was {}", noise_flatness);
+
+        use ::dsp::dft;
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 8.0, 0.0);
+        let samples = tone.sample_range(0.0, 1.0, 64.0);
+        let input: Vec<Complex<f64>> = samples.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        let magnitude: Vec<f64> = dft(&input).iter().take(33).map(|x| x.magnitude()).collect();
+        let tone_flatness = spectral_flatness(&magnitude);
+        assert!(tone_flatness < 0.01, "Tone flatness was {}", tone_flatness);
+    }
+
+    #[test]
+    fn spectrum_db_of_tone_has_sharp_peak() {
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 1000.0, 0.4);
+        let samples = tone.sample_range(0.0, 1000.0/8000.0, 8000.0);
+        let (frequencies, magnitudes) = spectrum_db(&samples, 8000.0);
+        assert_eq!(frequencies.len(), 513);
+        assert_eq!(magnitudes.len(), 513);
+
+        let peak = magnitudes.iter().enumerate()
+            .fold(0, |best, (k, &m)| if m > magnitudes[best] { k } else { best });
+        assert!((frequencies[peak] - 1000.0).abs() < 8000.0/1024.0, "Peak was at {} Hz", frequencies[peak]);
+        assert!(magnitudes[peak] > -1.5, "Peak was {} dB", magnitudes[peak]);
+        for (k, m) in magnitudes.iter().enumerate() {
+            if k + 10 < peak || k > peak + 10 {
+                assert!(*m < magnitudes[peak] - 60.0, "Bin {} was {} dB", k, m);
+            }
+        }
+    }
+
+    #[test]
+    fn leftover_samples_are_ignored() {
+        let samples: Vec<f64> = noise::white(100, 1.0, 99);
+        let window = vec![1.0; 32];
+        let full = welch_psd(&samples[..96], &window, 0);
+        let with_leftovers = welch_psd(&samples, &window, 0);
+        assert_eq!(full, with_leftovers);
+        assert!(welch_psd(&samples[..20], &window, 0).is_empty());
+    }
+
+    #[test]
+    fn spectral_subtraction_improves_snr() {
+        use ::dsp::rms;
+
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 440.0, 0.0);
+        let clean = tone.sample_range(0.0, 1.0, 8000.0);
+        let noise: Vec<f64> = noise::white(clean.len(), 0.5, 99);
+        let noisy: Vec<f64> = clean.iter().zip(noise.iter()).map(|(x, n)| x + n).collect();
+        let profile: Vec<f64> = noise::white(4000, 0.5, 100);
+
+        let cleaned = spectral_subtract(&noisy, &profile, 8000.0);
+        assert_eq!(cleaned.len(), noisy.len());
+
+        let snr = |output: &[f64]| {
+            let error: Vec<f64> = output.iter().zip(clean.iter()).map(|(y, x)| y - x).collect();
+            amplitude_to_db(rms(&clean) / rms(&error))
+        };
+        let before = snr(&noisy);
+        let after = snr(&cleaned);
+        assert!(after > before + 6.0, "SNR went from {} dB to {} dB", before, after);
+    }
+
+    #[test]
+    fn spectral_subtraction_without_noise_is_transparent() {
+        let tone: Sinusoid<f64> = Sinusoid::new(0.8, 300.0, 0.4);
+        let clean = tone.sample_range(0.0, 0.1, 8000.0);
+        let unchanged = spectral_subtract(&clean, &[], 8000.0);
+        for (a, b) in unchanged.iter().zip(clean.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn zeroing_high_bins_is_low_pass() {
+        use ::dsp::ifft;
+
+        let low: Sinusoid<f64> = Sinusoid::new(1.0, 4.0, 0.2);
+        let high: Sinusoid<f64> = Sinusoid::new(0.5, 40.0, -1.0);
+        let low_samples = low.sample_range(0.0, 1.0, 128.0);
+        let mixed: Vec<Complex<f64>> = low_samples.iter().zip(high.sample_range(0.0, 1.0, 128.0))
+            .map(|(a, b)| Complex::new(a + b, 0.0))
+            .collect();
+
+        let mut spectrum = fft(&mixed).unwrap();
+        // keep everything below 20 Hz, and the matching negative frequencies
+        let gains: Vec<f64> = (0..128).map(|k| if (20..=108).contains(&k) { 0.0 } else { 1.0 }).collect();
+        apply_gain_curve(&mut spectrum, &gains);
+        let filtered = ifft(&spectrum).unwrap();
+        for (y, x) in filtered.iter().zip(low_samples.iter()) {
+            assert!((y.real - x).abs() < 1e-9);
+            assert!(y.imag.abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn gain_curve_needs_gain_for_each_bin() {
+        let mut spectrum = vec![Complex::new(1.0_f64, 0.0); 4];
+        apply_gain_curve(&mut spectrum, &[1.0, 1.0]);
+    }
+
+    #[test]
+    fn tonality_separates_tones_from_noise() {
+        // off bin, and not a power of two long
+        let tone: Sinusoid<f64> = Sinusoid::new(0.3, 1234.5, 0.7);
+        let score = tonality(&tone.sample_range(0.0, 0.15, 8000.0));
+        assert!(score > 0.99, "Tone scored {}", score);
+
+        let noise: Vec<f64> = noise::white(1200, 1.0, 31);
+        let score = tonality(&noise);
+        assert!(score < 0.05, "Noise scored {}", score);
+    }
+}
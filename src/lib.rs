@@ -3,5 +3,9 @@
 extern crate quickcheck;
 
 pub mod complex;
+pub mod dsp;
 pub mod num_traits;
+pub mod signal;
 pub mod sinusoid;
+pub mod stereo;
+pub mod waves;
@@ -8,6 +8,8 @@ pub trait Trig {
     fn asin(self) -> Self;
     fn acos(self) -> Self;
     fn atan2(self, other: Self) -> Self;
+
+    fn tanh(self) -> Self;
 }
 
 macro_rules! impl_float_trig {
@@ -31,6 +33,9 @@ macro_rules! impl_float_trig {
             fn atan2(self, other: Self) -> Self {
                 self.atan2(other)
             }
+            fn tanh(self) -> Self {
+                self.tanh()
+            }
         }
     }
 }
@@ -101,10 +106,16 @@ pub trait FractionOps {
     fn two_pi() -> Self;
     fn half_pi() -> Self;
     fn zero() -> Self;
+
+    /// A practical tolerance for comparing values that have been
+    /// through a few calculations. This is much looser than the
+    /// machine epsilon, which is only the error of a single rounding.
+    /// It's the default tolerance for `ApproxEq::approx_eq`.
+    fn epsilon() -> Self;
 }
 
 macro_rules! impl_fraction_float {
-    ($t: ty, $pi: expr) => {
+    ($t: ty, $pi: expr, $epsilon: expr) => {
         impl FractionOps for $t {
             fn recip(self) -> Self {
                 self.recip()
@@ -121,9 +132,318 @@ macro_rules! impl_fraction_float {
             fn zero() -> Self {
                 0.0
             }
+            fn epsilon() -> Self {
+                $epsilon
+            }
+        }
+    }
+}
+
+impl_fraction_float!(f32, std::f32::consts::PI, 1e-5);
+impl_fraction_float!(f64, std::f64::consts::PI, 1e-10);
+
+
+pub trait NumCast {
+    /// For integer types, this saturates at the maximum of the type,
+    /// like `from_f64` does.
+    fn from_usize(v: usize) -> Self;
+    /// For integer types, this truncates towards zero and saturates at
+    /// the bounds of the type.
+    fn from_f64(v: f64) -> Self;
+    /// Truncates towards zero, saturating at the bounds of usize.
+    fn to_usize(self) -> usize;
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_num_cast {
+    (float $t: ty) => {
+        impl_num_cast!($t, |v: usize| v as $t, |v: $t| v as usize);
+    };
+    (int $t: ty) => {
+        impl_num_cast!(
+            $t,
+            |v: usize| <$t as ::std::convert::TryFrom<usize>>::try_from(v).unwrap_or(<$t>::MAX),
+            |v: $t| <usize as ::std::convert::TryFrom<$t>>::try_from(v).unwrap_or(if v < 0 as $t { 0 } else { usize::MAX })
+        );
+    };
+    ($t: ty, $from_usize: expr, $to_usize: expr) => {
+        impl NumCast for $t {
+            fn from_usize(v: usize) -> Self {
+                $from_usize(v)
+            }
+            fn from_f64(v: f64) -> Self {
+                v as Self
+            }
+            fn to_usize(self) -> usize {
+                $to_usize(self)
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    }
+}
+
+impl_num_cast!(float f32);
+impl_num_cast!(float f64);
+impl_num_cast!(int i8);
+impl_num_cast!(int i16);
+impl_num_cast!(int i32);
+impl_num_cast!(int i64);
+impl_num_cast!(int u8);
+impl_num_cast!(int u16);
+impl_num_cast!(int u32);
+impl_num_cast!(int u64);
+
+
+pub trait One {
+    fn one() -> Self;
+}
+
+macro_rules! impl_one {
+    ($t: ty, $one: expr) => {
+        impl One for $t {
+            fn one() -> Self {
+                $one
+            }
+        }
+    }
+}
+
+impl_one!(f32, 1.0);
+impl_one!(f64, 1.0);
+impl_one!(i8, 1);
+impl_one!(i16, 1);
+impl_one!(i32, 1);
+impl_one!(i64, 1);
+impl_one!(u8, 1);
+impl_one!(u16, 1);
+impl_one!(u32, 1);
+impl_one!(u64, 1);
+
+
+pub trait Exp {
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn log2(self) -> Self;
+    fn log10(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+}
+
+macro_rules! impl_float_exp {
+    ($t: ty) => {
+        impl Exp for $t {
+            fn exp(self) -> Self {
+                self.exp()
+            }
+            fn ln(self) -> Self {
+                self.ln()
+            }
+            fn log2(self) -> Self {
+                self.log2()
+            }
+            fn log10(self) -> Self {
+                self.log10()
+            }
+            fn powf(self, n: Self) -> Self {
+                self.powf(n)
+            }
         }
     }
 }
 
-impl_fraction_float!(f32, std::f32::consts::PI);
-impl_fraction_float!(f64, std::f64::consts::PI);
+impl_float_exp!(f32);
+impl_float_exp!(f64);
+
+
+pub trait Abs {
+    fn abs(self) -> Self;
+}
+
+macro_rules! impl_abs {
+    ($t: ty) => {
+        impl Abs for $t {
+            fn abs(self) -> Self {
+                self.abs()
+            }
+        }
+    }
+}
+
+impl_abs!(f32);
+impl_abs!(f64);
+impl_abs!(i8);
+impl_abs!(i16);
+impl_abs!(i32);
+impl_abs!(i64);
+
+
+/// Integer arithmetic that reports overflow instead of wrapping.
+pub trait CheckedOps: Sized {
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+/// Integer arithmetic that clamps to the bounds of the type instead of
+/// wrapping.
+pub trait SaturatingOps {
+    fn saturating_add(self, other: Self) -> Self;
+    fn saturating_sub(self, other: Self) -> Self;
+    fn saturating_mul(self, other: Self) -> Self;
+    /// `self·b + c·d`, worked out exactly and only clamped at the end,
+    /// so large products that cancel out still give the right answer.
+    fn saturating_mul_add(self, b: Self, c: Self, d: Self) -> Self;
+    /// `self·b - c·d`, worked out exactly and only clamped at the end.
+    fn saturating_mul_sub(self, b: Self, c: Self, d: Self) -> Self;
+}
+
+macro_rules! clamp_wide {
+    ($v: expr, $t: ty, $wide: ty) => {
+        <$t as ::std::convert::TryFrom<$wide>>::try_from($v).unwrap_or(if $v > 0 { <$t>::MAX } else { <$t>::MIN })
+    }
+}
+
+macro_rules! impl_int_overflow_ops {
+    ($t: ty, $wide: ty) => {
+        impl CheckedOps for $t {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                self.checked_sub(other)
+            }
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                self.checked_mul(other)
+            }
+        }
+        impl SaturatingOps for $t {
+            fn saturating_add(self, other: Self) -> Self {
+                self.saturating_add(other)
+            }
+            fn saturating_sub(self, other: Self) -> Self {
+                self.saturating_sub(other)
+            }
+            fn saturating_mul(self, other: Self) -> Self {
+                self.saturating_mul(other)
+            }
+            fn saturating_mul_add(self, b: Self, c: Self, d: Self) -> Self {
+                let (ab, cd) = (self as $wide * b as $wide, c as $wide * d as $wide);
+                // the sum can only overflow the wide type if both
+                // products have the same sign
+                match ab.checked_add(cd) {
+                    Some(sum) => clamp_wide!(sum, $t, $wide),
+                    None if ab > 0 => <$t>::MAX,
+                    None => <$t>::MIN
+                }
+            }
+            fn saturating_mul_sub(self, b: Self, c: Self, d: Self) -> Self {
+                let (ab, cd) = (self as $wide * b as $wide, c as $wide * d as $wide);
+                match ab.checked_sub(cd) {
+                    Some(difference) => clamp_wide!(difference, $t, $wide),
+                    None if ab > cd => <$t>::MAX,
+                    None => <$t>::MIN
+                }
+            }
+        }
+    }
+}
+
+impl_int_overflow_ops!(i8, i128);
+impl_int_overflow_ops!(i16, i128);
+impl_int_overflow_ops!(i32, i128);
+impl_int_overflow_ops!(i64, i128);
+impl_int_overflow_ops!(u8, u128);
+impl_int_overflow_ops!(u16, u128);
+impl_int_overflow_ops!(u32, u128);
+impl_int_overflow_ops!(u64, u128);
+
+/// Equality within a tolerance, for values with rounding errors.
+pub trait ApproxEq {
+    /// Compares with `FractionOps::epsilon` as the tolerance.
+    ///
+    /// ```
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// assert!((0.1 + 0.2 as f64).approx_eq(0.3));
+    /// assert!(!(0.1 as f64).approx_eq(0.1001));
+    /// ```
+    fn approx_eq(self, other: Self) -> bool;
+
+    /// The difference can be at most `tolerance`, scaled up by the
+    /// larger magnitude when that is above 1. The tolerance is
+    /// absolute for small values and relative for large ones.
+    ///
+    /// ```
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// assert!((1000.0 as f64).approx_eq_within(1000.5, 1e-3));
+    /// assert!(!(0.0 as f64).approx_eq_within(0.5, 1e-3));
+    /// ```
+    fn approx_eq_within(self, other: Self, tolerance: Self) -> bool;
+}
+
+macro_rules! impl_approx_eq_float {
+    ($t: ty) => {
+        impl ApproxEq for $t {
+            fn approx_eq(self, other: Self) -> bool {
+                self.approx_eq_within(other, <$t as FractionOps>::epsilon())
+            }
+            fn approx_eq_within(self, other: Self, tolerance: Self) -> bool {
+                let scale = self.abs().max(other.abs()).max(1.0);
+                (self - other).abs() <= tolerance*scale
+            }
+        }
+    }
+}
+
+impl_approx_eq_float!(f32);
+impl_approx_eq_float!(f64);
+
+pub trait Float: Trig + Pow + Exp + Abs + SignedArithmeticOps + FractionOps + NumCast + One + ApproxEq + PartialOrd + Copy {}
+impl<T> Float for T where T: Trig + Pow + Exp + Abs + SignedArithmeticOps + FractionOps + NumCast + One + ApproxEq + PartialOrd + Copy {}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epsilon_is_small_but_looser_than_machine_epsilon() {
+        let f32_epsilon = <f32 as FractionOps>::epsilon();
+        assert!(f32_epsilon > f32::EPSILON);
+        assert!(f32_epsilon < 1e-3);
+        // adding up a hundred rounding errors is still within tolerance
+        let sum = (0..100).fold(0.0f32, |acc, _| acc + 0.01);
+        assert!(sum.approx_eq(1.0));
+        assert!(!sum.approx_eq(1.001));
+
+        let f64_epsilon = <f64 as FractionOps>::epsilon();
+        assert!(f64_epsilon > f64::EPSILON);
+        assert!(f64_epsilon < f32_epsilon as f64);
+    }
+
+    #[test]
+    fn float_casts_from_f64() {
+        assert_eq!(f32::from_f64(0.25), 0.25);
+        assert_eq!(f64::from_f64(0.995), 0.995);
+        assert_eq!(f32::from_usize(44100), 44100.0);
+        assert_eq!(f64::from_usize(3).to_f64(), 3.0);
+    }
+
+    #[test]
+    fn int_casts_truncate_and_saturate() {
+        assert_eq!(i16::from_f64(2.9), 2);
+        assert_eq!(i16::from_f64(-2.9), -2);
+        assert_eq!(i16::from_f64(1e9), i16::MAX);
+        assert_eq!(u8::from_f64(-1.0), 0);
+        assert_eq!(i32::from_usize(7), 7);
+        assert_eq!(i8::from_usize(200), i8::MAX);
+        assert_eq!(u16::from_usize(70000), u16::MAX);
+        assert_eq!(u32::from_f64(f64::NAN), 0);
+        assert_eq!(i64::from_usize(5).to_usize(), 5);
+        assert_eq!((-1i32).to_usize(), 0);
+        assert_eq!(i64::MIN.to_usize(), 0);
+        assert_eq!(u64::MAX.to_usize(), u64::MAX as usize);
+    }
+}
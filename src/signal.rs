@@ -0,0 +1,66 @@
+use ::num_traits::{ArithmeticOps, NumCast};
+
+/// Anything that has a value at every point in time, and so can be
+/// sampled.
+///
+/// This lets code be written once for any shape of signal, like
+/// `Sinusoid` or the waves in the `waves` module.
+///
+/// ```
+/// use worthe_signals::signal::Signal;
+/// use worthe_signals::sinusoid::Sinusoid;
+/// use worthe_signals::waves::SquareWave;
+///
+/// let signals: Vec<Box<dyn Signal<f32>>> = vec![
+///     Box::new(Sinusoid::new(1.0, 1.0, 0.0)),
+///     Box::new(SquareWave::new(1.0, 1.0, 0.0))
+/// ];
+/// for signal in signals {
+///     assert!((signal.sample(0.0) - 1.0).abs() < 1e-6);
+/// }
+/// ```
+pub trait Signal<T> {
+    fn sample(&self, t: T) -> T;
+
+    /// Samples the signal at all of the points in a range.
+    ///
+    /// Start value is inclusive. End value is exclusive.
+    fn sample_range(&self, start: T, end: T, sample_rate: T) -> Vec<T>
+        where T: ArithmeticOps + NumCast + PartialOrd + Copy {
+        let mut result = Vec::new();
+        let mut i: usize = 0;
+        loop {
+            let t = start + T::from_usize(i)/sample_rate;
+            if t >= end {
+                break;
+            }
+            result.push(self.sample(t));
+            i += 1;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::sinusoid::Sinusoid;
+    use ::waves::SquareWave;
+
+    fn mean_level<S>(signal: &S) -> f64 where S: Signal<f64> {
+        let samples = signal.sample_range(0.0, 1.0, 1000.0);
+        samples.iter().sum::<f64>() / samples.len() as f64
+    }
+
+    #[test]
+    fn generic_over_signals() {
+        let sinusoid = Sinusoid::new(1.0, 5.0, 0.0);
+        let square = SquareWave::new(1.0, 5.0, 0.0);
+        assert!(mean_level(&sinusoid).abs() < 1e-9);
+        assert!(mean_level(&square).abs() < 1e-9);
+
+        let shifted = SquareWave::new(2.0, 5.0, 0.0);
+        assert_eq!(shifted.sample_range(0.0, 0.2, 40.0), vec![2.0, 2.0, -2.0, -2.0, -2.0, -2.0, 2.0, 2.0]);
+    }
+}
@@ -1,6 +1,8 @@
 use std::cmp::{PartialOrd};
-use ::num_traits::{Trig, Pow, ArithmeticOps, SignedArithmeticOps, FractionOps};
+use ::num_traits::{Trig, Pow, Exp, Abs, ArithmeticOps, SignedArithmeticOps, FractionOps, NumCast, One, Float};
 use ::complex::Complex;
+use ::dsp::{forward_any_length, goertzel, hann_window, parabolic_interp};
+use ::signal::Signal;
 
 /// A data structure representing a sinusoid. AKA the sin or cos functions.
 ///
@@ -18,9 +20,9 @@ pub struct Sinusoid<T> {
 impl<T> Sinusoid<T> {
     pub fn new(amplitude:T, frequency: T, phase: T) -> Sinusoid<T> {
         Sinusoid {
-            amplitude: amplitude,
-            frequency: frequency,
-            phase: phase
+            amplitude,
+            frequency,
+            phase
         }
     }
 }
@@ -40,11 +42,56 @@ impl<T> Sinusoid<T> where T: FractionOps + Copy {
     }
 }
 
+impl<T> Sinusoid<T> where T: Exp + ArithmeticOps + NumCast + Copy {
+    /// Shifts the frequency by a number of equal tempered semitones.
+    ///
+    /// Each semitone multiplies the frequency by 2^(1/12), so 12
+    /// semitones is one octave. Negative values transpose downwards.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let a4 = Sinusoid::new(1.0 as f32, 440.0, 0.0);
+    /// let a5 = a4.transpose_semitones(12.0);
+    /// assert!((a5.frequency - 880.0).abs() < 0.001);
+    /// ```
+    pub fn transpose_semitones(self, semitones: T) -> Sinusoid<T> {
+        let ratio = T::from_f64(2.0).powf(semitones / T::from_f64(12.0));
+        Sinusoid::new(self.amplitude, self.frequency*ratio, self.phase)
+    }
+}
+
+impl<T> Sinusoid<T> where T: ArithmeticOps + Abs + Copy {
+    /// When two sinusoids with close frequencies are added sample by
+    /// sample, they interfere to give a tone whose loudness pulses at
+    /// the difference between their frequencies. This is the beat
+    /// frequency.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let a = Sinusoid::new(1.0 as f32, 440.0, 0.0);
+    /// let b = Sinusoid::new(1.0 as f32, 444.0, 0.0);
+    /// assert_eq!(a.beat_frequency(&b), 4.0);
+    /// assert_eq!(b.beat_frequency(&a), 4.0);
+    /// ```
+    pub fn beat_frequency(&self, other: &Sinusoid<T>) -> T {
+        (self.frequency - other.frequency).abs()
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum AddSinusoidError {
     DifferentFrequency
 }
 
+#[derive(Debug, PartialEq)]
+pub enum AliasingError {
+    /// The sample rate is less than twice the frequency, so the
+    /// samples would look like a lower frequency tone.
+    BelowNyquistRate
+}
+
 impl<T> Sinusoid<T> where T: Trig + Pow + ArithmeticOps + Copy {
     /// Converts the sinusoid to phasor form
     ///
@@ -55,7 +102,7 @@ impl<T> Sinusoid<T> where T: Trig + Pow + ArithmeticOps + Copy {
     /// use worthe_signals::sinusoid::Sinusoid;
     /// use worthe_signals::complex::Complex;
     /// use std::f32;
-    
+    ///
     /// let pure_real = Sinusoid::new(1.0 as f32, 0.5, 0.0);    
     /// let pure_real_phasor = pure_real.to_phasor();
     /// assert!((pure_real_phasor.real - 1.0).abs() < f32::EPSILON);
@@ -72,6 +119,54 @@ impl<T> Sinusoid<T> where T: Trig + Pow + ArithmeticOps + Copy {
 
 }
 
+impl<T> Sinusoid<T> where T: Trig + Pow + SignedArithmeticOps + NumCast + Copy {
+    /// Converts the sinusoid to the pair of phasors in its two sided
+    /// spectrum, `(A/2)·e^(jθ)` at the positive frequency and
+    /// `(A/2)·e^(-jθ)` at the negative frequency.
+    ///
+    /// A real sinusoid is the sum of these two phasors rotating in
+    /// opposite directions, which cancels out the imaginary parts.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// let sinusoid = Sinusoid::new(3.0 as f64, 50.0, 0.7);
+    /// let (positive, negative) = sinusoid.to_phasor_pair();
+    /// assert_eq!(positive.conjugate(), negative);
+    ///
+    /// let sum = positive + negative;
+    /// assert!(sum.real.approx_eq(3.0*0.7f64.cos()));
+    /// assert!(sum.imag.approx_eq(0.0));
+    /// ```
+    pub fn to_phasor_pair(self) -> (Complex<T>, Complex<T>) {
+        let positive = Complex::from_polar(self.amplitude / T::from_f64(2.0), self.phase);
+        (positive, positive.conjugate())
+    }
+}
+
+impl<T> Sinusoid<T> where T: Trig + Pow + ArithmeticOps + FractionOps + Copy {
+    /// The rotating phasor, `A·e^(j(ωt + θ))`, at a given point in
+    /// time.
+    ///
+    /// Where `to_phasor` gives the phasor at t=0, this one has been
+    /// rotated by the time that has passed. Its real part is the
+    /// sinusoid's value at that time.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let sinusoid = Sinusoid::new(2.0 as f32, 3.0, 0.5);
+    /// for &t in &[0.0, 0.1, 0.25, 0.7, 1.3] {
+    ///     assert!((sinusoid.phasor_at(t).real - sinusoid.sample(t)).abs() < 1e-4);
+    ///     assert!((sinusoid.phasor_at(t).magnitude() - 2.0).abs() < 1e-4);
+    /// }
+    /// ```
+    pub fn phasor_at(&self, t: T) -> Complex<T> {
+        Complex::from_polar(self.amplitude, self.instantaneous_phase(t))
+    }
+}
+
 impl<T> Sinusoid<T> where T: Trig + Pow + SignedArithmeticOps + FractionOps + Copy {
     /// Splits the sinusoid into cos and sin components
     ///
@@ -124,6 +219,7 @@ impl<T> Sinusoid<T> where T: Trig + Pow + ArithmeticOps + PartialEq + Copy {
     /// let sin2 = Sinusoid::new(4.0 as f32, 1.0, -f32::consts::FRAC_PI_2);
     /// assert_eq!(Ok(Sinusoid::new(5.0, 1.0, -2.21429743558818100603413092035707408014009529080286529335)), sin1.add(sin2));
     /// ```
+    #[allow(clippy::should_implement_trait)]
     pub fn add(self, other: Sinusoid<T>) -> Result<Self, AddSinusoidError> {
         if self.frequency != other.frequency {
             Err(AddSinusoidError::DifferentFrequency)
@@ -141,6 +237,24 @@ impl<T> Sinusoid<T> where T: Trig + Pow + ArithmeticOps + PartialEq + Copy {
 }
 
 impl<T> Sinusoid<T> where T: FractionOps + ArithmeticOps + Trig + Copy {
+    /// Creates a sinusoid from its frequency in radians per second,
+    /// rather than its frequency in Hz.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    /// use std::f64;
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// let sinusoid = Sinusoid::from_radial(1.0, 2.0*f64::consts::PI, 0.0);
+    /// assert!(sinusoid.frequency.approx_eq(1.0));
+    ///
+    /// let round_trip = Sinusoid::from_radial(1.0 as f64, 3.5, 0.2);
+    /// assert!(round_trip.radial_frequency().approx_eq(3.5));
+    /// ```
+    pub fn from_radial(amplitude: T, radial_frequency: T, phase: T) -> Sinusoid<T> {
+        Sinusoid::new(amplitude, radial_frequency / T::two_pi(), phase)
+    }
+
     /// Frequency can be considered in terms of the signal's number of
     /// repetitions per second (referred to just as the frequency), or
     /// the frequency in radians.
@@ -148,13 +262,32 @@ impl<T> Sinusoid<T> where T: FractionOps + ArithmeticOps + Trig + Copy {
     /// use worthe_signals::sinusoid::Sinusoid;
     /// use std::f32;
     ///
-    /// let sinusoid = Sinusoid::new(1.0 as f32, 1.0, 0.0);
+    /// let sinusoid: Sinusoid<f32> = Sinusoid::new(1.0, 1.0, 0.0);
     /// assert!((sinusoid.radial_frequency()-2.0*f32::consts::PI) < f32::EPSILON);
     /// ```
     pub fn radial_frequency(&self) -> T {
         T::two_pi()*self.frequency
     }
 
+    /// The instantaneous phase is the argument to the cosine at a given
+    /// point in time, `ωt + θ`.
+    ///
+    /// It is not wrapped, so it keeps increasing by 2π every period.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    /// use std::f32;
+    ///
+    /// let sinusoid = Sinusoid::new(1.0 as f32, 2.0, 0.3);
+    /// assert!((sinusoid.instantaneous_phase(0.0) - 0.3).abs() < f32::EPSILON);
+    ///
+    /// let advance = sinusoid.instantaneous_phase(1.25) - sinusoid.instantaneous_phase(0.75);
+    /// assert!((advance - 2.0*f32::consts::PI).abs() < 1e-5);
+    /// ```
+    pub fn instantaneous_phase(&self, t: T) -> T {
+        self.radial_frequency()*t + self.phase
+    }
+
     /// A sinusoid can be sampled to get its value at a given point in
     /// time.
     ///
@@ -173,7 +306,51 @@ impl<T> Sinusoid<T> where T: FractionOps + ArithmeticOps + Trig + Copy {
         (self.radial_frequency()*(t%self.period()) + self.phase).cos() * self.amplitude
     }
 }
-impl<T> Sinusoid<T> where T: FractionOps + ArithmeticOps + From<u16> + Trig + Copy + PartialOrd {
+impl<T> Signal<T> for Sinusoid<T> where T: FractionOps + ArithmeticOps + Trig + Copy {
+    fn sample(&self, t: T) -> T {
+        Sinusoid::sample(self, t)
+    }
+}
+
+impl<T> Sinusoid<T> where T: FractionOps + ArithmeticOps + NumCast + Trig + PartialEq + Copy {
+    /// The energy of the sinusoid over an interval, `∫ sample(t)² dt`
+    /// from `start` to `end`.
+    ///
+    /// This is calculated exactly from the antiderivative
+    /// `A²/2·(t + sin(2ωt + 2θ)/2ω)`, rather than by numerical
+    /// integration. A sinusoid with a frequency of zero is a constant
+    /// of `A·cos(θ)`, so its energy is `A²cos²(θ)` times the duration.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// // over a whole number of periods, the sin term cancels out
+    /// let sinusoid = Sinusoid::new(2.0 as f64, 3.0, 0.4);
+    /// let duration = 2.0 * sinusoid.period();
+    /// let energy = sinusoid.energy(0.5, 0.5 + duration);
+    /// assert!(energy.approx_eq(4.0/2.0*duration));
+    ///
+    /// let dc = Sinusoid::new(2.0 as f64, 0.0, 0.0);
+    /// assert!(dc.energy(0.0, 3.0).approx_eq(12.0));
+    /// ```
+    pub fn energy(&self, start: T, end: T) -> T {
+        let power = self.amplitude*self.amplitude;
+        if self.frequency == T::zero() {
+            let level = self.phase.cos();
+            return power*level*level*(end - start);
+        }
+
+        let two = T::from_f64(2.0);
+        let omega = self.radial_frequency();
+        let antiderivative = |t: T| {
+            power/two * (t + (two*omega*t + two*self.phase).sin()/(two*omega))
+        };
+        antiderivative(end) - antiderivative(start)
+    }
+}
+
+impl<T> Sinusoid<T> where T: FractionOps + ArithmeticOps + NumCast + One + Trig + Copy + PartialOrd {
     /// Sometimes, it's useful to sample at all of the points in a range
     ///
     /// Start value is inclusive. End value is exclusive.
@@ -193,26 +370,442 @@ impl<T> Sinusoid<T> where T: FractionOps + ArithmeticOps + From<u16> + Trig + Co
     /// }
     /// ```
     pub fn sample_range(&self, start: T, end: T, sample_rate: T) -> Vec<T> {
-        let mut result = Vec::new();
-        let mut i: u16 = 0;
-        loop {
-            let t = start + T::from(i)/sample_rate;
-            if t >= end {
-                break;
-            }
-            result.push(self.sample(t));
-            i += 1;
+        Signal::sample_range(self, start, end, sample_rate)
+    }
+
+    /// An endless iterator of samples, at `t = 0, 1/rate, 2/rate, ...`.
+    ///
+    /// The samples are at the same points in time as `sample_range`
+    /// starting from 0 would give.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let samples: Vec<f32> = Sinusoid::new(1.0 as f32, 1.0, 0.0).into_sample_iter(4.0).take(3).collect();
+    /// assert!((samples[0] - 1.0).abs() < 1e-6);
+    /// assert!(samples[1].abs() < 1e-6);
+    /// assert!((samples[2] + 1.0).abs() < 1e-6);
+    /// ```
+    pub fn into_sample_iter(self, sample_rate: T) -> impl Iterator<Item = T> {
+        (0usize..).map(move |i| self.sample(T::from_usize(i)/sample_rate))
+    }
+
+    /// Fills `out` with consecutive samples starting at `start`,
+    /// without allocating.
+    ///
+    /// The samples are at the same points in time as `sample_range`
+    /// would give, so this can be used to render a long signal one
+    /// buffer at a time.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let sinusoid = Sinusoid::new(1.0 as f32, 1.0, 0.0);
+    /// let mut buffer = [0.0; 4];
+    /// sinusoid.sample_into(0.0, 4.0, &mut buffer);
+    /// assert!((buffer[2] + 1.0).abs() < 1e-6);
+    /// ```
+    pub fn sample_into(&self, start: T, sample_rate: T, out: &mut [T]) {
+        for (i, sample) in out.iter_mut().enumerate() {
+            *sample = self.sample(start + T::from_usize(i)/sample_rate);
         }
-        
-        result
+    }
+
+    /// Exactly `num_samples` samples, at `t = start + i/rate`.
+    ///
+    /// The end of `sample_range` is found by accumulating time, so a
+    /// range whose length is a whole number of samples can come out
+    /// one sample short or long. Use this when the count matters more
+    /// than the end time.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let samples = Sinusoid::new(1.0 as f64, 1.0, 0.0).sample_count(0.0, 3, 10.0);
+    /// assert_eq!(samples.len(), 3);
+    /// ```
+    pub fn sample_count(&self, start: T, num_samples: usize, sample_rate: T) -> Vec<T> {
+        (0..num_samples)
+            .map(|i| self.sample(start + T::from_usize(i)/sample_rate))
+            .collect()
+    }
+
+    /// Samples the sinusoid as 16 bit PCM, like a DAC or WAV file
+    /// expects.
+    ///
+    /// A sample of `full_scale` becomes 32767, and `-full_scale`
+    /// becomes -32767. Samples are rounded to the nearest integer, and
+    /// anything beyond full scale is clipped to the range of i16.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let sinusoid = Sinusoid::new(1.0 as f32, 1.0, 0.0);
+    /// let pcm = sinusoid.sample_range_i16(0.0, 1.0, 4.0, 1.0);
+    /// assert_eq!(pcm, vec![32767, 0, -32767, 0]);
+    /// ```
+    pub fn sample_range_i16(&self, start: T, end: T, sample_rate: T, full_scale: T) -> Vec<i16> {
+        let scale = T::from_usize(i16::MAX as usize) / full_scale;
+        self.sample_range(start, end, sample_rate).into_iter()
+            .map(|x| (x*scale).to_f64().round() as i16)
+            .collect()
+    }
+
+    /// Amplitude modulates this sinusoid, the carrier, with a message
+    /// signal.
+    ///
+    /// The output is `(1 + m·message(t))·carrier(t)`, sampled over
+    /// the same range as `sample_range`. Unlike multiplying the two
+    /// signals together, the DC term keeps the carrier present in the
+    /// output, with the message appearing as sidebands at the carrier
+    /// plus and minus the message frequency.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let carrier = Sinusoid::new(1.0 as f32, 100.0, 0.0);
+    /// let message = Sinusoid::new(1.0 as f32, 5.0, 0.0);
+    /// let am = carrier.amplitude_modulate(&message, 0.5, 0.0, 1.0, 1000.0);
+    /// assert_eq!(am.len(), 1000);
+    /// assert!((am[0] - 1.5).abs() < 1e-6);
+    /// ```
+    pub fn amplitude_modulate(&self, message: &Sinusoid<T>, modulation_index: T, start: T, end: T, sample_rate: T) -> Vec<T> {
+        let carrier = self.sample_range(start, end, sample_rate);
+        let message = message.sample_range(start, end, sample_rate);
+        carrier.into_iter().zip(message)
+            .map(|(c, m)| (T::one() + modulation_index*m) * c)
+            .collect()
+    }
+}
+
+impl<T> Sinusoid<T> where T: Float {
+    /// Checks whether two sinusoids are the same signal, to within
+    /// `tolerance`, even if they are written differently.
+    ///
+    /// Phases that differ by a multiple of 2π are the same, and so are
+    /// a negative amplitude and a positive amplitude with the phase
+    /// flipped by π. If both amplitudes are within tolerance of 0, the
+    /// phases are ignored, since they don't affect the signal.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    /// use std::f64::consts::PI;
+    ///
+    /// let a = Sinusoid::new(2.0 as f64, 10.0, 0.5);
+    /// assert!(a.equivalent(&Sinusoid::new(2.0, 10.0, 0.5 + 4.0*PI), 1e-9));
+    /// assert!(a.equivalent(&Sinusoid::new(-2.0, 10.0, 0.5 - PI), 1e-9));
+    /// assert!(!a.equivalent(&Sinusoid::new(2.0, 10.0, -0.5), 1e-9));
+    /// ```
+    pub fn equivalent(&self, other: &Sinusoid<T>, tolerance: T) -> bool {
+        let a = self.clone().canonical();
+        let b = other.clone().canonical();
+        let phase_difference = (a.phase - b.phase).sin().atan2((a.phase - b.phase).cos());
+        let silent = a.amplitude <= tolerance && b.amplitude <= tolerance;
+
+        (a.amplitude - b.amplitude).abs() <= tolerance
+            && (a.frequency - b.frequency).abs() <= tolerance
+            && (silent || phase_difference.abs() <= tolerance)
+    }
+
+    /// The same sinusoid in a standard form, with a non-negative
+    /// amplitude and the phase wrapped to be more than -π and at most
+    /// π. A negative amplitude is made positive by shifting the phase
+    /// by π.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    /// use std::f64::consts::PI;
+    /// use worthe_signals::num_traits::ApproxEq;
+    ///
+    /// let flipped = Sinusoid::new(-3.0 as f64, 2.0, 0.5).canonical();
+    /// assert_eq!(flipped.amplitude, 3.0);
+    /// assert!(flipped.phase.approx_eq(0.5 - PI));
+    ///
+    /// let wrapped = Sinusoid::new(1.0 as f64, 2.0, 3.0*PI).canonical();
+    /// assert!(wrapped.phase.approx_eq(PI));
+    /// ```
+    pub fn canonical(self) -> Sinusoid<T> {
+        let (amplitude, phase) = if self.amplitude < T::zero() {
+            (-self.amplitude, self.phase + T::pi())
+        } else {
+            (self.amplitude, self.phase)
+        };
+        let wrapped = phase.sin().atan2(phase.cos());
+        let wrapped = if wrapped <= -T::pi() { wrapped + T::two_pi() } else { wrapped };
+        Sinusoid::new(amplitude, self.frequency, wrapped)
+    }
+
+    /// Samples `duration` seconds of the sinusoid, starting from t=0,
+    /// giving `round(duration·sample_rate)` samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AliasingError::BelowNyquistRate` if the sample rate is
+    /// less than twice the sinusoid's frequency.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::{Sinusoid, AliasingError};
+    ///
+    /// let tone = Sinusoid::new(1.0 as f32, 440.0, 0.0);
+    /// assert_eq!(tone.to_samples(0.5, 44100.0).unwrap().len(), 22050);
+    /// assert_eq!(tone.to_samples(0.5, 800.0), Err(AliasingError::BelowNyquistRate));
+    /// ```
+    pub fn to_samples(&self, duration: T, sample_rate: T) -> Result<Vec<T>, AliasingError> {
+        if sample_rate < T::from_f64(2.0)*self.frequency.abs() {
+            return Err(AliasingError::BelowNyquistRate);
+        }
+        let count = (duration*sample_rate + T::from_f64(0.5)).to_usize();
+        Ok((0..count).map(|n| self.sample(T::from_usize(n) / sample_rate)).collect())
+    }
+
+    /// Estimates the amplitude, frequency and phase of a single tone
+    /// from samples of it, taken starting at t=0.
+    ///
+    /// The frequency is found from the strongest bin of the Hann
+    /// windowed spectrum, refined between bins by fitting a parabola
+    /// through the log magnitudes of the peak and its neighbours. The
+    /// amplitude and phase are then measured at that refined
+    /// frequency. The estimate gets better the more periods of the
+    /// tone there are in the samples.
+    ///
+    /// If there are no samples, there's nothing to estimate and the
+    /// result has zero amplitude.
+    ///
+    /// ```
+    /// use worthe_signals::sinusoid::Sinusoid;
+    ///
+    /// let tone = Sinusoid::new(0.5 as f64, 50.0, 0.25);
+    /// let samples = tone.sample_range(0.0, 1.0, 400.0);
+    /// let estimate = Sinusoid::estimate_from_samples(&samples, 400.0);
+    /// assert!((estimate.frequency - 50.0).abs() < 1e-6);
+    /// assert!((estimate.amplitude - 0.5).abs() < 1e-6);
+    /// assert!((estimate.phase - 0.25).abs() < 1e-6);
+    /// ```
+    pub fn estimate_from_samples(samples: &[T], sample_rate: T) -> Sinusoid<T> {
+        if samples.is_empty() {
+            return Sinusoid::new(T::zero(), T::zero(), T::zero());
+        }
+
+        let len = samples.len();
+        let two = T::from_f64(2.0);
+        let windowed: Vec<Complex<T>> = samples.iter().zip(hann_window(len))
+            .map(|(&x, w)| Complex::new(x*w, T::zero()))
+            .collect();
+        let magnitudes: Vec<T> = forward_any_length(&windowed).into_iter()
+            .take(len/2 + 1)
+            .map(|x| x.magnitude())
+            .collect();
+
+        let peak = magnitudes.iter().enumerate()
+            .fold(0, |best, (k, &m)| if m > magnitudes[best] { k } else { best });
+        let has_neighbours = peak > 0 && peak + 1 < magnitudes.len()
+            && magnitudes[peak-1] > T::zero() && magnitudes[peak+1] > T::zero();
+        let offset = if has_neighbours {
+            parabolic_interp(magnitudes[peak-1].ln(), magnitudes[peak].ln(), magnitudes[peak+1].ln()).0
+        } else {
+            T::zero()
+        };
+        let frequency = (T::from_usize(peak) + offset) * sample_rate / T::from_usize(len);
+
+        let component = goertzel(samples, frequency, sample_rate);
+        // DC and Nyquist have no mirror image in the negative
+        // frequencies, so they hold the whole amplitude
+        let scale = if peak == 0 || 2*peak == len { T::from_usize(len) } else { T::from_usize(len) / two };
+        Sinusoid::new(component.magnitude() / scale, frequency, component.angle())
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::f32;
+    use ::num_traits::ApproxEq;
+
+    #[test]
+    fn to_samples_renders_rounded_duration() {
+        let tone: Sinusoid<f64> = Sinusoid::new(2.0, 15.0, 0.3);
+        let samples = tone.to_samples(0.1234, 100.0).unwrap();
+        assert_eq!(samples.len(), 12);
+        let expected = tone.sample_range(0.0, 0.12, 100.0);
+        for (&a, &b) in samples.iter().zip(expected.iter()) {
+            assert!(a.approx_eq(b));
+        }
+        // exactly at the Nyquist rate is allowed
+        assert!(tone.to_samples(1.0, 30.0).is_ok());
+    }
+
+    #[test]
+    fn to_samples_rejects_aliasing() {
+        let tone: Sinusoid<f64> = Sinusoid::new(1.0, 15.0, 0.0);
+        assert_eq!(tone.to_samples(1.0, 29.9), Err(AliasingError::BelowNyquistRate));
+        let negative: Sinusoid<f64> = Sinusoid::new(1.0, -15.0, 0.0);
+        assert_eq!(negative.to_samples(1.0, 20.0), Err(AliasingError::BelowNyquistRate));
+    }
+
+    #[test]
+    fn sample_iter_matches_sample_range() {
+        let sinusoid: Sinusoid<f64> = Sinusoid::new(0.5, 3.0, 1.1);
+        let expected = sinusoid.sample_range(0.0, 1000.0, 100.0);
+        let samples: Vec<f64> = sinusoid.into_sample_iter(100.0).take(expected.len()).collect();
+        assert_eq!(samples, expected);
+    }
+
+    #[test]
+    fn sample_into_matches_sample_range() {
+        let sinusoid: Sinusoid<f64> = Sinusoid::new(1.5, 7.0, -0.4);
+        let expected = sinusoid.sample_range(0.25, 1.25, 100.0);
+        let mut buffer = [0.0; 64];
+        sinusoid.sample_into(0.25, 100.0, &mut buffer);
+        assert_eq!(buffer[..], expected[..64]);
+    }
+
+    #[test]
+    fn sample_count_is_exact() {
+        let sinusoid: Sinusoid<f64> = Sinusoid::new(0.7, 11.0, 0.9);
+        for &rate in &[3.0, 44100.0, 48000.0, 96000.0 / 7.0] {
+            for &num_samples in &[0, 1, 2, 99, 1000, 4097] {
+                let start = 0.1;
+                let samples = sinusoid.sample_count(start, num_samples, rate);
+                assert_eq!(samples.len(), num_samples);
+                for (i, &sample) in samples.iter().enumerate() {
+                    assert_eq!(sample, sinusoid.sample(start + i as f64 / rate));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn equivalent_ignores_phase_wrapping() {
+        use std::f64::consts::PI;
+        let a: Sinusoid<f64> = Sinusoid::new(1.5, 3.0, 3.1);
+        assert!(a.equivalent(&Sinusoid::new(1.5, 3.0, 3.1 - 2.0*PI), 1e-9));
+        assert!(a.equivalent(&Sinusoid::new(1.5, 3.0, 3.1 + 6.0*PI), 1e-9));
+        // just either side of ±π are close together
+        let b: Sinusoid<f64> = Sinusoid::new(1.0, 3.0, PI - 1e-6);
+        assert!(b.equivalent(&Sinusoid::new(1.0, 3.0, -PI + 1e-6), 1e-5));
+        assert!(!a.equivalent(&Sinusoid::new(1.5, 3.1, 3.1), 1e-9));
+    }
+
+    #[test]
+    fn equivalent_ignores_amplitude_sign() {
+        use std::f64::consts::PI;
+        let a: Sinusoid<f64> = Sinusoid::new(-2.0, 5.0, 0.25);
+        assert!(a.equivalent(&Sinusoid::new(2.0, 5.0, 0.25 + PI), 1e-9));
+        assert!(a.equivalent(&Sinusoid::new(2.0, 5.0, 0.25 - PI), 1e-9));
+        assert!(!a.equivalent(&Sinusoid::new(2.0, 5.0, 0.25), 1e-9));
+
+        let silent: Sinusoid<f64> = Sinusoid::new(0.0, 5.0, 1.0);
+        assert!(silent.equivalent(&Sinusoid::new(0.0, 5.0, -2.0), 1e-9));
+    }
 
+    #[test]
+    fn canonical_phase_excludes_minus_pi() {
+        use std::f64::consts::PI;
+        let edge: Sinusoid<f64> = Sinusoid::new(1.0, 1.0, -PI).canonical();
+        assert!(edge.phase.approx_eq(PI));
+        let flipped: Sinusoid<f64> = Sinusoid::new(-1.0, 1.0, 0.0).canonical();
+        assert!(flipped.phase.approx_eq(PI));
+    }
+
+    #[test]
+    fn sample_range_f64() {
+        let sinusoid: Sinusoid<f64> = Sinusoid::new(2.0, 5.0, 0.3);
+        let samples = sinusoid.sample_range(0.0, 1.0, 100.0);
+        assert_eq!(samples.len(), 100);
+        for (i, sample) in samples.iter().enumerate() {
+            let expected = sinusoid.sample(i as f64 / 100.0);
+            assert!(sample.approx_eq(expected), "Sample {} was {}, expected {}", i, sample, expected);
+        }
+        assert_eq!(sinusoid.sample_range(0.0, 1.0, 49.0).len(), 49);
+    }
+
+    #[test]
+    fn sample_range_f32_unchanged() {
+        // the times are start + i/rate, as they were with the From<u16>
+        // bound, rather than a multiple of 1/rate, which rounds
+        // differently and can change the number of samples
+        let sinusoid: Sinusoid<f32> = Sinusoid::new(1.0, 3.0, 0.5);
+        for &rate in &[41.0, 47.0, 55.0, 61.0, 44100.0, 48000.0] {
+            let samples = sinusoid.sample_range(0.0, 1.0, rate);
+            let mut expected = Vec::new();
+            let mut i: u16 = 0;
+            loop {
+                let t = f32::from(i)/rate;
+                if t >= 1.0 {
+                    break;
+                }
+                expected.push(sinusoid.sample(t));
+                i += 1;
+            }
+            assert_eq!(samples, expected, "Samples at {} Hz changed", rate);
+        }
+    }
+
+    #[test]
+    fn amplitude_modulation_sidebands() {
+        use ::complex::Complex;
+        use ::dsp::dft;
+
+        let carrier: Sinusoid<f64> = Sinusoid::new(1.0, 100.0, 0.0);
+        let message = Sinusoid::new(1.0, 10.0, 0.0);
+        let am = carrier.amplitude_modulate(&message, 0.5, 0.0, 1.0, 1000.0);
+        let input: Vec<Complex<f64>> = am.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        let spectrum: Vec<f64> = dft(&input).iter().map(|x| x.magnitude() / 500.0).collect();
+
+        // one second of samples gives 1 Hz bins
+        assert!((spectrum[100] - 1.0).abs() < 1e-6, "Carrier was {}", spectrum[100]);
+        assert!((spectrum[90] - 0.25).abs() < 1e-6, "Lower sideband was {}", spectrum[90]);
+        assert!((spectrum[110] - 0.25).abs() < 1e-6, "Upper sideband was {}", spectrum[110]);
+        for (k, x) in spectrum.iter().enumerate().take(500) {
+            if k != 90 && k != 100 && k != 110 {
+                assert!(*x < 1e-6, "Bin {} was {}", k, x);
+            }
+        }
+    }
+
+    #[test]
+    fn estimate_off_bin_sinusoid() {
+        let tone: Sinusoid<f64> = Sinusoid::new(0.8, 123.4, 0.6);
+        // 1024 samples goes through the fft, and 1000 through the dft
+        for &len in &[1000, 1024] {
+            let samples = tone.sample_count(0.0, len, 1000.0);
+            let estimate = Sinusoid::estimate_from_samples(&samples, 1000.0);
+            assert!((estimate.frequency - 123.4).abs() < 0.05, "Frequency from {} samples was {}", len, estimate.frequency);
+            assert!((estimate.amplitude - 0.8).abs() < 0.01, "Amplitude from {} samples was {}", len, estimate.amplitude);
+            assert!((estimate.phase - 0.6).abs() < 0.1, "Phase from {} samples was {}", len, estimate.phase);
+        }
+    }
+
+    #[test]
+    fn estimate_at_nyquist() {
+        let tone: Sinusoid<f64> = Sinusoid::new(0.7, 500.0, 0.0);
+        let samples = tone.sample_count(0.0, 1000, 1000.0);
+        let estimate = Sinusoid::estimate_from_samples(&samples, 1000.0);
+        assert!((estimate.frequency - 500.0).abs() < 1e-9, "Frequency was {}", estimate.frequency);
+        assert!((estimate.amplitude - 0.7).abs() < 1e-9, "Amplitude was {}", estimate.amplitude);
+    }
+
+    #[test]
+    fn pcm_reaches_full_scale() {
+        let sinusoid: Sinusoid<f64> = Sinusoid::new(1.0, 3.0, 0.1);
+        let pcm = sinusoid.sample_range_i16(0.0, 1.0, 8000.0, 1.0);
+        assert_eq!(pcm.len(), 8000);
+        let max = *pcm.iter().max().unwrap();
+        let min = *pcm.iter().min().unwrap();
+        assert!(max > 32700, "Max was {}", max);
+        assert!(min < -32700, "Min was {}", min);
+    }
+
+    #[test]
+    fn pcm_clips_beyond_full_scale() {
+        let loud: Sinusoid<f64> = Sinusoid::new(2.0, 1.0, 0.0);
+        let pcm = loud.sample_range_i16(0.0, 1.0, 4.0, 1.0);
+        assert_eq!(pcm, vec![i16::MAX, 0, i16::MIN, 0]);
+    }
+
+    #[test]
+    fn sample_range_more_than_u16_samples() {
+        let sinusoid: Sinusoid<f32> = Sinusoid::new(1.0, 1.0, 0.0);
+        let samples = sinusoid.sample_range(0.0, 1.0, 70000.0);
+        assert_eq!(samples.len(), 70000);
+    }
 }
-*/
@@ -0,0 +1,194 @@
+use ::num_traits::Float;
+
+/// A buffer of two channel samples, stored as a separate vector for
+/// each channel.
+///
+/// Most of the processing in this crate works on one channel at a
+/// time, so `map_channels` can be used to apply it to both.
+///
+/// The channels always have the same number of samples. This is
+/// checked when the buffer is built, and the channels are only
+/// exposed as slices afterwards, so the methods that pair up left and
+/// right samples can't fail.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StereoBuffer<T> {
+    left: Vec<T>,
+    right: Vec<T>
+}
+
+/// The ways that stereo data can fail to pair up into left and right
+/// samples.
+#[derive(Debug, PartialEq)]
+pub enum StereoBufferError {
+    /// The left and right channels have different numbers of samples.
+    ChannelLengthMismatch,
+    /// Interleaved data had an odd length, so its last left sample has
+    /// no right sample.
+    OddInterleavedLength
+}
+
+impl<T> StereoBuffer<T> {
+    /// # Errors
+    ///
+    /// Both channels need to have the same number of samples.
+    ///
+    /// ```
+    /// use worthe_signals::stereo::{StereoBuffer, StereoBufferError};
+    ///
+    /// assert!(StereoBuffer::new(vec![1, 2], vec![3, 4]).is_ok());
+    /// assert_eq!(Err(StereoBufferError::ChannelLengthMismatch), StereoBuffer::new(vec![1, 2], vec![3]));
+    /// ```
+    pub fn new(left: Vec<T>, right: Vec<T>) -> Result<StereoBuffer<T>, StereoBufferError> {
+        if left.len() != right.len() {
+            Err(StereoBufferError::ChannelLengthMismatch)
+        } else {
+            Ok(StereoBuffer { left, right })
+        }
+    }
+
+    pub fn left(&self) -> &[T] {
+        &self.left
+    }
+
+    pub fn right(&self) -> &[T] {
+        &self.right
+    }
+
+    /// Gives up the buffer, returning the left and right channels.
+    pub fn into_channels(self) -> (Vec<T>, Vec<T>) {
+        (self.left, self.right)
+    }
+
+    /// Applies the same processing to each channel.
+    ///
+    /// # Errors
+    ///
+    /// The processing needs to give the same number of samples for
+    /// each channel.
+    ///
+    /// ```
+    /// use worthe_signals::stereo::StereoBuffer;
+    /// use worthe_signals::dsp::clip;
+    ///
+    /// let buffer = StereoBuffer::new(vec![0.5 as f32, 2.0], vec![-3.0, 0.25]).unwrap();
+    /// let clipped = buffer.map_channels(|channel| clip(channel, 1.0)).unwrap();
+    /// assert_eq!(clipped.left(), &[0.5, 1.0]);
+    /// assert_eq!(clipped.right(), &[-1.0, 0.25]);
+    /// ```
+    pub fn map_channels<F, U>(&self, f: F) -> Result<StereoBuffer<U>, StereoBufferError> where F: Fn(&[T]) -> Vec<U> {
+        StereoBuffer::new(f(&self.left), f(&self.right))
+    }
+}
+
+impl<T> StereoBuffer<T> where T: Copy {
+    /// Combines the channels into a single vector, alternating between
+    /// left and right samples.
+    ///
+    /// ```
+    /// use worthe_signals::stereo::StereoBuffer;
+    ///
+    /// let buffer = StereoBuffer::new(vec![1, 2], vec![3, 4]).unwrap();
+    /// assert_eq!(buffer.interleave(), vec![1, 3, 2, 4]);
+    /// ```
+    pub fn interleave(&self) -> Vec<T> {
+        let mut interleaved = Vec::with_capacity(2*self.left.len());
+        for (&l, &r) in self.left.iter().zip(self.right.iter()) {
+            interleaved.push(l);
+            interleaved.push(r);
+        }
+        interleaved
+    }
+
+    /// Splits alternating left and right samples into separate
+    /// channels.
+    ///
+    /// # Errors
+    ///
+    /// Every left sample needs a matching right sample, so the
+    /// interleaved data needs to have an even length.
+    ///
+    /// ```
+    /// use worthe_signals::stereo::StereoBuffer;
+    ///
+    /// let buffer = StereoBuffer::deinterleave(&[1, 3, 2, 4]).unwrap();
+    /// assert_eq!(buffer.left(), &[1, 2]);
+    /// assert_eq!(buffer.right(), &[3, 4]);
+    /// ```
+    pub fn deinterleave(interleaved: &[T]) -> Result<StereoBuffer<T>, StereoBufferError> {
+        if interleaved.len() % 2 == 1 {
+            return Err(StereoBufferError::OddInterleavedLength);
+        }
+        let left = interleaved.iter().step_by(2).cloned().collect();
+        let right = interleaved.iter().skip(1).step_by(2).cloned().collect();
+        Ok(StereoBuffer { left, right })
+    }
+}
+
+impl<T> StereoBuffer<T> where T: Float {
+    /// Downmixes to a single channel, by averaging the left and right
+    /// samples.
+    ///
+    /// ```
+    /// use worthe_signals::stereo::StereoBuffer;
+    ///
+    /// let buffer = StereoBuffer::new(vec![1.0 as f32, 0.5], vec![0.0, 0.5]).unwrap();
+    /// assert_eq!(buffer.to_mono(), vec![0.5, 0.5]);
+    /// ```
+    pub fn to_mono(&self) -> Vec<T> {
+        let half = T::from_f64(0.5);
+        self.to_mono_weighted(half, half)
+    }
+
+    /// Downmixes to a single channel, as `left_gain·L + right_gain·R`.
+    ///
+    /// ```
+    /// use worthe_signals::stereo::StereoBuffer;
+    ///
+    /// let buffer = StereoBuffer::new(vec![1.0 as f32, 0.5], vec![0.0, 0.5]).unwrap();
+    /// assert_eq!(buffer.to_mono_weighted(1.0, -1.0), vec![1.0, 0.0]);
+    /// ```
+    pub fn to_mono_weighted(&self, left_gain: T, right_gain: T) -> Vec<T> {
+        self.left.iter().zip(self.right.iter())
+            .map(|(&l, &r)| left_gain*l + right_gain*r)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    quickcheck! {
+        fn interleave_round_trip(left: Vec<i32>, right: Vec<i32>) -> bool {
+            let len = left.len().min(right.len());
+            let buffer = StereoBuffer::new(left[..len].to_vec(), right[..len].to_vec()).unwrap();
+            let interleaved = buffer.interleave();
+            interleaved.len() == 2*len && StereoBuffer::deinterleave(&interleaved) == Ok(buffer)
+        }
+    }
+
+    #[test]
+    fn mismatched_channels_are_rejected() {
+        assert_eq!(StereoBuffer::new(vec![1.0], vec![]), Err(StereoBufferError::ChannelLengthMismatch));
+        let buffer = StereoBuffer::new(vec![1.0, 2.0], vec![3.0, 4.0]).unwrap();
+        assert_eq!(buffer.map_channels(|channel| channel[1..].to_vec()).map(StereoBuffer::into_channels), Ok((vec![2.0], vec![4.0])));
+        assert_eq!(buffer.map_channels(|channel| vec![channel[0]; channel[0] as usize]), Err(StereoBufferError::ChannelLengthMismatch));
+    }
+
+    #[test]
+    fn downmix_to_mono() {
+        use ::sinusoid::Sinusoid;
+        let tone: Vec<f64> = Sinusoid::new(0.8, 5.0, 0.2).sample_count(0.0, 64, 100.0);
+        let centred = StereoBuffer::new(tone.clone(), tone.clone()).unwrap();
+        assert_eq!(centred.to_mono(), tone);
+
+        let hard_left = StereoBuffer::new(tone.clone(), vec![0.0; 64]).unwrap();
+        let expected: Vec<f64> = tone.iter().map(|x| 0.5*x).collect();
+        assert_eq!(hard_left.to_mono(), expected);
+    }
+
+    #[test]
+    fn odd_interleaved_length_is_rejected() {
+        assert_eq!(StereoBuffer::deinterleave(&[1, 2, 3]), Err(StereoBufferError::OddInterleavedLength));
+    }
+}
@@ -0,0 +1,135 @@
+use ::num_traits::Float;
+use ::signal::Signal;
+
+/// How far through its current period a wave is, in the range [0, 1).
+fn cycle_position<T>(frequency: T, phase: T, t: T) -> T where T: Float {
+    let position = (frequency*t + phase/T::two_pi()) % T::one();
+    if position < T::zero() {
+        position + T::one()
+    } else {
+        position
+    }
+}
+
+/// A square wave, which alternates between `amplitude` and
+/// `-amplitude`.
+///
+/// The wave lines up with a `Sinusoid` of the same parameters, being
+/// positive whenever the sinusoid is positive.
+///
+/// ```
+/// use worthe_signals::signal::Signal;
+/// use worthe_signals::waves::SquareWave;
+///
+/// let square = SquareWave::new(1.0 as f32, 1.0, 0.0);
+/// assert_eq!(square.sample(0.0), 1.0);
+/// assert_eq!(square.sample(0.3), -1.0);
+/// assert_eq!(square.sample(0.8), 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SquareWave<T> {
+    pub amplitude: T,
+    pub frequency: T,
+    pub phase: T
+}
+
+impl<T> SquareWave<T> {
+    pub fn new(amplitude: T, frequency: T, phase: T) -> SquareWave<T> {
+        SquareWave {
+            amplitude,
+            frequency,
+            phase
+        }
+    }
+}
+
+impl<T> Signal<T> for SquareWave<T> where T: Float {
+    fn sample(&self, t: T) -> T {
+        let position = cycle_position(self.frequency, self.phase, t);
+        let quarter = T::from_f64(0.25);
+        if position < quarter || position >= T::one() - quarter {
+            self.amplitude
+        } else {
+            -self.amplitude
+        }
+    }
+}
+
+/// A sawtooth wave, which ramps linearly from `-amplitude` up to
+/// `amplitude` over each period, and then drops back down.
+///
+/// ```
+/// use worthe_signals::signal::Signal;
+/// use worthe_signals::waves::SawtoothWave;
+///
+/// let saw = SawtoothWave::new(1.0 as f32, 1.0, 0.0);
+/// assert_eq!(saw.sample(0.0), -1.0);
+/// assert_eq!(saw.sample(0.5), 0.0);
+/// assert_eq!(saw.sample(0.75), 0.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SawtoothWave<T> {
+    pub amplitude: T,
+    pub frequency: T,
+    pub phase: T
+}
+
+impl<T> SawtoothWave<T> {
+    pub fn new(amplitude: T, frequency: T, phase: T) -> SawtoothWave<T> {
+        SawtoothWave {
+            amplitude,
+            frequency,
+            phase
+        }
+    }
+}
+
+impl<T> Signal<T> for SawtoothWave<T> where T: Float {
+    fn sample(&self, t: T) -> T {
+        let position = cycle_position(self.frequency, self.phase, t);
+        let two = T::from_f64(2.0);
+        self.amplitude * (two*position - T::one())
+    }
+}
+
+/// A triangle wave, which ramps linearly between `amplitude` and
+/// `-amplitude`.
+///
+/// The wave lines up with a `Sinusoid` of the same parameters,
+/// reaching its peaks and crossing zero at the same times.
+///
+/// ```
+/// use worthe_signals::signal::Signal;
+/// use worthe_signals::waves::TriangleWave;
+///
+/// let triangle = TriangleWave::new(1.0 as f32, 1.0, 0.0);
+/// assert_eq!(triangle.sample(0.0), 1.0);
+/// assert_eq!(triangle.sample(0.25), 0.0);
+/// assert_eq!(triangle.sample(0.5), -1.0);
+/// assert_eq!(triangle.sample(0.875), 0.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriangleWave<T> {
+    pub amplitude: T,
+    pub frequency: T,
+    pub phase: T
+}
+
+impl<T> TriangleWave<T> {
+    pub fn new(amplitude: T, frequency: T, phase: T) -> TriangleWave<T> {
+        TriangleWave {
+            amplitude,
+            frequency,
+            phase
+        }
+    }
+}
+
+impl<T> Signal<T> for TriangleWave<T> where T: Float {
+    fn sample(&self, t: T) -> T {
+        let position = cycle_position(self.frequency, self.phase, t);
+        let half = T::from_f64(0.5);
+        let distance_from_middle = if position > half { position - half } else { half - position };
+        self.amplitude * (T::from_f64(4.0)*distance_from_middle - T::one())
+    }
+}